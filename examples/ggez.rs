@@ -43,6 +43,14 @@ impl ganache::Widget<GuiContext> for Panel {
         ganache::default_layout::minimum_size(&args, &self.0)
     }
 
+    fn measure(
+        &self,
+        args: ganache::MinimumSizeArgs<'_, GuiContext>,
+        available: ganache::Dimensions,
+    ) -> ganache::Dimensions {
+        ganache::default_layout::measure(&args, available, &self.0)
+    }
+
     fn layout_children(&self, mut args: ganache::LayoutChildrenArgs<'_, GuiContext>) {
         ganache::default_layout::layout_children(&mut args, &self.0)
     }
@@ -136,37 +144,31 @@ fn main() -> GameResult<()> {
     
     let mut gui = Gui::new(ganache::Bounds::zero());
 
-    let (panel_slot_id, _) = gui.add_slot_with_widget(
-        gui.root_slot_id(),
-        ganache::SlotInfo {
-            minimum_size: ganache::Dimensions::new(400.0, 300.0),
-            ..Default::default()
-        },
-        Panel(ganache::default_layout::Settings {
-            padding: 5.0,
-            child_spacing: 2.0,
-            axis: ganache::default_layout::Axis::Horizontal,
-        }),
-    );
-
-    gui.add_slot_with_widget(
-        panel_slot_id,
-        ganache::SlotInfo {
-            expand_x: true,
-            expand_y: true,
-            ..Default::default()
-        },
-        Button,
-    );
-    gui.add_slot_with_widget(
-        panel_slot_id,
-        ganache::SlotInfo {
-            expand_x: true,
-            expand_y: true,
-            ..Default::default()
-        },
-        Button,
-    );
+    let button_info = || ganache::SlotInfo {
+        expand_x: true,
+        expand_y: true,
+        ..Default::default()
+    };
+    gui.build(gui.root_slot_id())
+        .child(
+            ganache::SlotInfo {
+                minimum_size: ganache::Dimensions::new(400.0, 300.0),
+                ..Default::default()
+            },
+            Panel(ganache::default_layout::Settings {
+                padding: 5.0,
+                child_spacing: 2.0,
+                axis: ganache::default_layout::Axis::Horizontal,
+                ..Default::default()
+            }),
+            |panel| {
+                panel
+                    .leaf(button_info(), Button)
+                    .leaf(button_info(), Button);
+            },
+        )
+        .finish()
+        .unwrap();
 
     let gui_theme = ganache::Theme::new(());
     let mut example = Example {
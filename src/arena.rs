@@ -0,0 +1,90 @@
+//! Generational storage backing `Slots` and the widget table.
+//!
+//! Removing a value bumps the generation of its entry, so an `Index` handed out before the removal
+//! can be told apart from one pointing at whatever value reuses the entry later.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Index {
+    index: u32,
+    generation: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LookupError {
+    /// The index was never handed out by this arena.
+    Unknown,
+    /// The value the index referred to has been removed.
+    Stale,
+}
+
+struct Entry<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+pub struct Arena<T> {
+    entries: Vec<Entry<T>>,
+    free: Vec<u32>,
+}
+
+impl<T> Arena<T> {
+    pub fn new() -> Arena<T> {
+        Arena {
+            entries: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    pub fn insert(&mut self, value: T) -> Index {
+        if let Some(index) = self.free.pop() {
+            let entry = &mut self.entries[index as usize];
+            entry.value = Some(value);
+            Index {
+                index,
+                generation: entry.generation,
+            }
+        } else {
+            let index = self.entries.len() as u32;
+            self.entries.push(Entry {
+                generation: 0,
+                value: Some(value),
+            });
+            Index {
+                index,
+                generation: 0,
+            }
+        }
+    }
+
+    pub fn remove(&mut self, index: Index) -> Result<T, LookupError> {
+        self.get(index)?;
+        let entry = &mut self.entries[index.index as usize];
+        entry.generation = entry.generation.wrapping_add(1);
+        self.free.push(index.index);
+        Ok(entry.value.take().unwrap())
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.get(index).is_ok()
+    }
+
+    pub fn get(&self, index: Index) -> Result<&T, LookupError> {
+        let entry = self.entries
+            .get(index.index as usize)
+            .ok_or(LookupError::Unknown)?;
+        match entry.value {
+            Some(ref value) if entry.generation == index.generation => Ok(value),
+            _ => Err(LookupError::Stale),
+        }
+    }
+
+    pub fn get_mut(&mut self, index: Index) -> Result<&mut T, LookupError> {
+        let entry = self.entries
+            .get_mut(index.index as usize)
+            .ok_or(LookupError::Unknown)?;
+        match entry.value {
+            Some(ref mut value) if entry.generation == index.generation => Ok(value),
+            _ => Err(LookupError::Stale),
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! A nested builder for creating slot trees without repeating `add_slot_with_widget` calls.
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use crate::{Context, Gui, GuiError, SlotId, SlotInfo, Widget, WidgetHandle, WidgetId};
+
+/// Builds slots under a parent slot. Created with `Gui::build`.
+///
+/// Methods that configure "the current slot" (`name`, `style`) apply to the slot most recently
+/// added by this builder. Errors are deferred until `finish`, which removes everything this
+/// builder created if any step failed.
+pub struct TreeBuilder<'a, C: Context> {
+    gui: &'a mut Gui<C>,
+    parent_id: SlotId,
+    current_slot_id: Option<SlotId>,
+    created_slot_ids: Vec<SlotId>,
+    built: BuiltTree<C>,
+    error: Option<GuiError>,
+}
+
+impl<'a, C: Context> TreeBuilder<'a, C> {
+    pub(crate) fn new(gui: &'a mut Gui<C>, parent_id: SlotId) -> TreeBuilder<'a, C> {
+        TreeBuilder {
+            gui,
+            parent_id,
+            current_slot_id: None,
+            created_slot_ids: Vec::new(),
+            built: BuiltTree::new(),
+            error: None,
+        }
+    }
+
+    /// Adds a slot bound to `widget`, then calls `children` to add slots beneath it.
+    pub fn child<W, F>(&mut self, info: SlotInfo, widget: W, children: F) -> &mut Self
+    where
+        W: Widget<C>,
+        F: FnOnce(&mut TreeBuilder<'_, C>),
+    {
+        if let Some(slot_id) = self.add_slot(info) {
+            let widget_id = self.gui.add_widget(widget);
+            self.gui.slots.get_mut(slot_id).widget_id = Some(widget_id);
+            self.build_children(slot_id, children);
+        }
+        self
+    }
+
+    /// Adds a slot bound to `widget` that has no children.
+    pub fn leaf<W: Widget<C>>(&mut self, info: SlotInfo, widget: W) -> &mut Self {
+        self.child(info, widget, |_| {})
+    }
+
+    /// Adds a slot without a widget, then calls `children` to add slots beneath it.
+    pub fn slot<F>(&mut self, info: SlotInfo, children: F) -> &mut Self
+    where
+        F: FnOnce(&mut TreeBuilder<'_, C>),
+    {
+        if let Some(slot_id) = self.add_slot(info) {
+            self.build_children(slot_id, children);
+        }
+        self
+    }
+
+    /// Names the current slot. The name is also used to look the slot up in the `BuiltTree`
+    /// returned by `finish`, so it must be unique among all names given during this build.
+    pub fn name<T: Into<String>>(&mut self, name: T) -> &mut Self {
+        if self.error.is_some() {
+            return self;
+        }
+        let slot_id = self.current_slot_id.expect("no slot to name");
+        let name = name.into();
+        if self.built.slots.contains_key(&name) {
+            self.error = Some(GuiError::DuplicateName {
+                parent_id: Some(self.parent_id),
+                name,
+            });
+            return self;
+        }
+        if let Err(err) = self.gui.slots.try_set_name(slot_id, name.clone()) {
+            self.error = Some(err);
+            return self;
+        }
+        if let Some(widget_id) = self.gui.slots.get(slot_id).widget_id {
+            let type_id = self.gui.widgets
+                .get(widget_id.0)
+                .unwrap()
+                .as_any()
+                .type_id();
+            self.built.widgets.insert(name.clone(), (widget_id, type_id));
+        }
+        self.built.slots.insert(name, slot_id);
+        self
+    }
+
+    /// Overrides a style field of the current slot.
+    pub fn style<T: Into<String>, U: Into<C::StyleFieldValue>>(
+        &mut self,
+        field_name: T,
+        value: U,
+    ) -> &mut Self {
+        if self.error.is_some() {
+            return self;
+        }
+        let slot_id = self.current_slot_id.expect("no slot to style");
+        self.gui.override_slot_style(slot_id, field_name, value);
+        self
+    }
+
+    /// Returns the handles of all named slots, or the first error encountered while building.
+    pub fn finish(&mut self) -> Result<BuiltTree<C>, GuiError> {
+        if let Some(err) = self.error.take() {
+            for slot_id in self.created_slot_ids.drain(..) {
+                self.gui.remove_slot(slot_id);
+            }
+            return Err(err);
+        }
+        self.created_slot_ids.clear();
+        Ok(std::mem::replace(&mut self.built, BuiltTree::new()))
+    }
+
+    fn add_slot(&mut self, info: SlotInfo) -> Option<SlotId> {
+        if self.error.is_some() {
+            return None;
+        }
+        let result = self.gui.slots
+            .try_get(self.parent_id)
+            .map(|parent_slot| parent_slot.children().len())
+            .and_then(|index| self.gui.slots.try_insert_at(self.parent_id, index, info));
+        match result {
+            Ok(slot_id) => {
+                self.current_slot_id = Some(slot_id);
+                self.created_slot_ids.push(slot_id);
+                Some(slot_id)
+            }
+            Err(err) => {
+                self.error = Some(err);
+                None
+            }
+        }
+    }
+
+    fn build_children<F>(&mut self, slot_id: SlotId, children: F)
+    where
+        F: FnOnce(&mut TreeBuilder<'_, C>),
+    {
+        let mut builder = TreeBuilder {
+            gui: &mut *self.gui,
+            parent_id: slot_id,
+            current_slot_id: None,
+            // Nested slots are removed along with our own on failure, so they needn't be tracked.
+            created_slot_ids: Vec::new(),
+            built: std::mem::replace(&mut self.built, BuiltTree::new()),
+            error: None,
+        };
+        children(&mut builder);
+        self.built = builder.built;
+        self.error = builder.error;
+    }
+}
+
+/// The named slots and widgets created by a `TreeBuilder`.
+pub struct BuiltTree<C: Context> {
+    slots: HashMap<String, SlotId>,
+    widgets: HashMap<String, (WidgetId, TypeId)>,
+    phantom: PhantomData<C>,
+}
+
+impl<C: Context> BuiltTree<C> {
+    fn new() -> BuiltTree<C> {
+        BuiltTree {
+            slots: HashMap::new(),
+            widgets: HashMap::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn slot(&self, name: &str) -> Option<SlotId> {
+        self.slots.get(name).cloned()
+    }
+
+    /// Returns a handle to the widget of the slot named `name`, provided it is of type `W`.
+    pub fn widget<W: Widget<C>>(&self, name: &str) -> Option<WidgetHandle<C, W>> {
+        self.widgets
+            .get(name)
+            .filter(|&&(_, type_id)| type_id == TypeId::of::<W>())
+            .map(|&(widget_id, _)| WidgetHandle(widget_id, PhantomData))
+    }
+}
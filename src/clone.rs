@@ -0,0 +1,95 @@
+//! Duplicating live slot subtrees.
+
+use std::collections::HashMap;
+
+use crate::{Context, Gui, GuiError, SlotId, Widget};
+
+/// Produces boxed copies of a widget. Implemented for every widget that is `Clone`; widgets opt
+/// into cloning by returning themselves from `Widget::as_clone_widget`.
+pub trait CloneWidget<C: Context> {
+    fn clone_widget(&self) -> Box<dyn Widget<C>>;
+}
+
+impl<C: Context, W: Widget<C> + Clone> CloneWidget<C> for W {
+    fn clone_widget(&self) -> Box<dyn Widget<C>> {
+        Box::new(self.clone())
+    }
+}
+
+/// Clones `widget`, or fails if it doesn't opt into cloning.
+pub(super) fn clone_widget<C: Context>(
+    widget: &dyn Widget<C>,
+) -> Result<Box<dyn Widget<C>>, GuiError> {
+    widget
+        .as_clone_widget()
+        .map(CloneWidget::clone_widget)
+        .ok_or_else(|| GuiError::NotCloneable {
+            kind_id: widget.kind_id().to_string(),
+        })
+}
+
+impl<C: Context> Gui<C> {
+    /// Duplicates `slot_id` and its descendants, appending the copy to `new_parent_id`'s
+    /// children. Slot infos, names, style overrides and widgets are all copied; every widget in
+    /// the subtree must support `Widget::as_clone_widget`. Returns the id of the copy's root.
+    ///
+    /// The copy's root keeps its name only if `new_parent_id` has no other child by that name, so
+    /// a subtree can be duplicated next to itself.
+    pub fn clone_subtree(
+        &mut self,
+        slot_id: SlotId,
+        new_parent_id: SlotId,
+    ) -> Result<SlotId, GuiError>
+    where
+        C::StyleFieldValue: Clone,
+    {
+        self.slots.try_get(slot_id)?;
+        self.slots.try_get(new_parent_id)?;
+        if slot_id == self.root_slot_id {
+            return Err(GuiError::RootSlot);
+        }
+
+        // Clone every widget before touching the tree, so that failure leaves it unchanged. This
+        // also snapshots the subtree, which matters if `new_parent_id` lies within it.
+        let source_ids: Vec<SlotId> = std::iter::once(slot_id)
+            .chain(self.slots.descendants(slot_id))
+            .collect();
+        let mut widgets = Vec::with_capacity(source_ids.len());
+        for &source_id in &source_ids {
+            widgets.push(match self.slots.get(source_id).widget_id {
+                Some(widget_id) => Some(clone_widget(&**self.widgets.get(widget_id.0).unwrap())?),
+                None => None,
+            });
+        }
+
+        let mut new_ids: HashMap<SlotId, SlotId> = HashMap::new();
+        for (&source_id, widget) in source_ids.iter().zip(widgets) {
+            let source = self.slots.get(source_id);
+            let info = source.info.clone();
+            let name = source.name.clone();
+            let parent_id = if source_id == slot_id {
+                new_parent_id
+            } else {
+                new_ids[&source.parent.unwrap()]
+            };
+            let new_id = self.slots.add(parent_id, info);
+            if let Some(name) = name {
+                if source_id == slot_id {
+                    let _ = self.slots.try_set_name(new_id, name);
+                } else {
+                    self.slots.set_name(new_id, name);
+                }
+            }
+            if let Some(widget) = widget {
+                let widget_id = self.add_boxed_widget(widget);
+                self.slots.get_mut(new_id).widget_id = Some(widget_id);
+            }
+            if let Some(overrides) = self.slot_style_overrides.get(&source_id).cloned() {
+                self.slot_style_overrides.insert(new_id, overrides);
+            }
+            new_ids.insert(source_id, new_id);
+        }
+        self.dirty = true;
+        Ok(new_ids[&slot_id])
+    }
+}
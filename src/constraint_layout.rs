@@ -0,0 +1,436 @@
+//! A layout that positions children by solving linear constraints between their edges, using the
+//! Cassowary algorithm. Requires the `constraint_layout` feature.
+//!
+//! ```ignore
+//! let layout = ConstraintLayout::new();
+//! // The left edge of `a` is 8 past the right edge of `b`.
+//! layout.add_constraint(edge(a, Edge::Left).equals(edge(b, Edge::Right) + 8.0))?;
+//! // `a` is at least twice as wide as `c`, if possible.
+//! layout.add_constraint(
+//!     edge(a, Edge::Width).at_least(edge(c, Edge::Width) * 2.0).with_strength(Strength::Strong),
+//! )?;
+//! ```
+//!
+//! A container widget owns a `ConstraintLayout` and calls `ConstraintLayout::layout_children` from
+//! `Widget::layout_children`. The solver is kept between layouts, so only the container's size
+//! and its children's minimum sizes are updated each time.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops;
+
+use cassowary::strength;
+use cassowary::{Constraint as SolverConstraint, RelationalOperator, Solver, Term, Variable};
+
+use crate::{scalar, Scalar, Context, Dimensions, GuiError, LayoutChildrenArgs, SlotId};
+
+/// A quantity of a slot (or of the container) that constraints can refer to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Edge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Width,
+    Height,
+    CenterX,
+    CenterY,
+}
+
+/// What an `Edge` belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// The container doing the layout. Its left and top edges are always zero, since children are
+    /// positioned relative to it.
+    Container,
+    /// One of the container's children.
+    Slot(SlotId),
+}
+
+/// How strongly the solver tries to satisfy a constraint. Only `Required` constraints can make the
+/// system unsatisfiable; the others are satisfied as well as possible, in order of strength.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strength {
+    Required,
+    Strong,
+    Medium,
+    Weak,
+}
+
+impl Strength {
+    fn value(self) -> f64 {
+        match self {
+            Strength::Required => strength::REQUIRED,
+            Strength::Strong => strength::STRONG,
+            Strength::Medium => strength::MEDIUM,
+            Strength::Weak => strength::WEAK,
+        }
+    }
+}
+
+/// The container's size is suggested to the solver just below `Required`, since edit variables
+/// can't be required.
+const CONTAINER_SIZE_STRENGTH: f64 = strength::REQUIRED - 1.0;
+
+/// A child's minimum size is `Strong` rather than `Required`, so that minimum sizes changing
+/// between layouts can't make the system unsatisfiable.
+const MINIMUM_SIZE_STRENGTH: Strength = Strength::Strong;
+
+/// A linear combination of edges plus a constant.
+#[derive(Debug, Clone)]
+pub struct Expression {
+    terms: Vec<(Target, Edge, f64)>,
+    constant: f64,
+}
+
+/// An edge of a child slot.
+pub fn edge(slot_id: SlotId, edge: Edge) -> Expression {
+    Expression {
+        terms: vec![(Target::Slot(slot_id), edge, 1.0)],
+        constant: 0.0,
+    }
+}
+
+/// An edge of the container.
+pub fn container(edge: Edge) -> Expression {
+    Expression {
+        terms: vec![(Target::Container, edge, 1.0)],
+        constant: 0.0,
+    }
+}
+
+impl Expression {
+    pub fn constant(value: f64) -> Expression {
+        Expression {
+            terms: Vec::new(),
+            constant: value,
+        }
+    }
+
+    pub fn equals<T: Into<Expression>>(self, rhs: T) -> Constraint {
+        Constraint::new(self, RelationalOperator::Equal, rhs.into())
+    }
+
+    pub fn at_least<T: Into<Expression>>(self, rhs: T) -> Constraint {
+        Constraint::new(self, RelationalOperator::GreaterOrEqual, rhs.into())
+    }
+
+    pub fn at_most<T: Into<Expression>>(self, rhs: T) -> Constraint {
+        Constraint::new(self, RelationalOperator::LessOrEqual, rhs.into())
+    }
+}
+
+impl From<f64> for Expression {
+    fn from(value: f64) -> Expression {
+        Expression::constant(value)
+    }
+}
+
+impl<T: Into<Expression>> ops::Add<T> for Expression {
+    type Output = Expression;
+
+    fn add(mut self, rhs: T) -> Expression {
+        let rhs = rhs.into();
+        self.terms.extend(rhs.terms);
+        self.constant += rhs.constant;
+        self
+    }
+}
+
+impl<T: Into<Expression>> ops::Sub<T> for Expression {
+    type Output = Expression;
+
+    fn sub(self, rhs: T) -> Expression {
+        self + rhs.into() * -1.0
+    }
+}
+
+impl ops::Mul<f64> for Expression {
+    type Output = Expression;
+
+    fn mul(mut self, rhs: f64) -> Expression {
+        for term in &mut self.terms {
+            term.2 *= rhs;
+        }
+        self.constant *= rhs;
+        self
+    }
+}
+
+/// A relation between two expressions, created with `Expression::equals`, `at_least` or
+/// `at_most`. Constraints are `Required` unless given another strength.
+#[derive(Debug, Clone)]
+pub struct Constraint {
+    lhs: Expression,
+    operator: RelationalOperator,
+    rhs: Expression,
+    strength: Strength,
+}
+
+impl Constraint {
+    fn new(lhs: Expression, operator: RelationalOperator, rhs: Expression) -> Constraint {
+        Constraint {
+            lhs,
+            operator,
+            rhs,
+            strength: Strength::Required,
+        }
+    }
+
+    pub fn with_strength(mut self, strength: Strength) -> Constraint {
+        self.strength = strength;
+        self
+    }
+}
+
+/// Identifies a constraint added to a `ConstraintLayout`, for removing it later.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConstraintHandle(SolverConstraint);
+
+/// The solver variables of a child slot. Right, bottom and centre edges are expressed in terms of
+/// these.
+#[derive(Clone, Copy)]
+struct SlotVariables {
+    left: Variable,
+    top: Variable,
+    width: Variable,
+    height: Variable,
+}
+
+struct State {
+    solver: Solver,
+    container_width: Variable,
+    container_height: Variable,
+    slots: HashMap<SlotId, SlotVariables>,
+    /// The minimum size constraints currently in the solver for each child, and the size they were
+    /// created for.
+    minimum_sizes: HashMap<SlotId, (Dimensions, [SolverConstraint; 2])>,
+}
+
+impl State {
+    fn slot_variables(&mut self, slot_id: SlotId) -> SlotVariables {
+        let solver = &mut self.solver;
+        *self.slots.entry(slot_id).or_insert_with(|| {
+            let variables = SlotVariables {
+                left: Variable::new(),
+                top: Variable::new(),
+                width: Variable::new(),
+                height: Variable::new(),
+            };
+            for &size in &[variables.width, variables.height] {
+                let non_negative = SolverConstraint::new(
+                    cassowary::Expression::from_term(Term {
+                        variable: size,
+                        coefficient: 1.0,
+                    }),
+                    RelationalOperator::GreaterOrEqual,
+                    strength::REQUIRED,
+                );
+                // A lone `size >= 0` on a fresh variable is always satisfiable.
+                solver.add_constraint(non_negative).unwrap();
+            }
+            variables
+        })
+    }
+
+    /// The weighted variables that `edge` of `target` stands for.
+    fn edge_terms(&mut self, target: Target, edge: Edge) -> Vec<(Variable, f64)> {
+        let (left, top, width, height) = match target {
+            Target::Container => (None, None, self.container_width, self.container_height),
+            Target::Slot(slot_id) => {
+                let variables = self.slot_variables(slot_id);
+                (Some(variables.left), Some(variables.top), variables.width, variables.height)
+            }
+        };
+        let mut terms = Vec::with_capacity(2);
+        let (origin, size, size_coefficient) = match edge {
+            Edge::Left => (left, None, 0.0),
+            Edge::Top => (top, None, 0.0),
+            Edge::Right => (left, Some(width), 1.0),
+            Edge::Bottom => (top, Some(height), 1.0),
+            Edge::CenterX => (left, Some(width), 0.5),
+            Edge::CenterY => (top, Some(height), 0.5),
+            Edge::Width => (None, Some(width), 1.0),
+            Edge::Height => (None, Some(height), 1.0),
+        };
+        if let Some(origin) = origin {
+            terms.push((origin, 1.0));
+        }
+        if let Some(size) = size {
+            terms.push((size, size_coefficient));
+        }
+        terms
+    }
+
+    fn solver_constraint(&mut self, constraint: &Constraint) -> SolverConstraint {
+        // Move everything to the left hand side: `lhs - rhs <op> 0`.
+        let mut terms = Vec::new();
+        let constant = constraint.lhs.constant - constraint.rhs.constant;
+        let sides = [(&constraint.lhs, 1.0), (&constraint.rhs, -1.0)];
+        for &(side, sign) in &sides {
+            for &(target, edge, coefficient) in &side.terms {
+                let edge_terms = self.edge_terms(target, edge);
+                terms.extend(edge_terms.into_iter().map(|(variable, edge_coefficient)| Term {
+                    variable,
+                    coefficient: sign * coefficient * edge_coefficient,
+                }));
+            }
+        }
+        SolverConstraint::new(
+            cassowary::Expression::new(terms, constant),
+            constraint.operator,
+            constraint.strength.value(),
+        )
+    }
+
+    fn update_minimum_size(&mut self, slot_id: SlotId, minimum_size: Dimensions) {
+        if let Some(&(current_size, _)) = self.minimum_sizes.get(&slot_id) {
+            if current_size == minimum_size {
+                return;
+            }
+        }
+        if let Some((_, constraints)) = self.minimum_sizes.remove(&slot_id) {
+            for constraint in &constraints {
+                self.solver.remove_constraint(constraint).unwrap();
+            }
+        }
+        let constraints = [
+            self.solver_constraint(
+                &edge(slot_id, Edge::Width)
+                    .at_least(from_scalar(minimum_size.width))
+                    .with_strength(MINIMUM_SIZE_STRENGTH),
+            ),
+            self.solver_constraint(
+                &edge(slot_id, Edge::Height)
+                    .at_least(from_scalar(minimum_size.height))
+                    .with_strength(MINIMUM_SIZE_STRENGTH),
+            ),
+        ];
+        for constraint in &constraints {
+            // Non-required constraints never make the system unsatisfiable.
+            self.solver.add_constraint(constraint.clone()).unwrap();
+        }
+        self.minimum_sizes.insert(slot_id, (minimum_size, constraints));
+    }
+}
+
+/// Keeps a Cassowary solver and the constraints between a container's children. See the module
+/// documentation.
+pub struct ConstraintLayout {
+    state: RefCell<State>,
+}
+
+impl ConstraintLayout {
+    pub fn new() -> ConstraintLayout {
+        let mut solver = Solver::new();
+        let container_width = Variable::new();
+        let container_height = Variable::new();
+        for &variable in &[container_width, container_height] {
+            solver.add_edit_variable(variable, CONTAINER_SIZE_STRENGTH).unwrap();
+        }
+        ConstraintLayout {
+            state: RefCell::new(State {
+                solver,
+                container_width,
+                container_height,
+                slots: HashMap::new(),
+                minimum_sizes: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Adds `constraint` to the solver. Fails with `GuiError::UnsatisfiableConstraint` if it is
+    /// `Required` and conflicts with the required constraints already added.
+    pub fn add_constraint(&self, constraint: Constraint) -> Result<ConstraintHandle, GuiError> {
+        let mut state = self.state.borrow_mut();
+        let solver_constraint = state.solver_constraint(&constraint);
+        state
+            .solver
+            .add_constraint(solver_constraint.clone())
+            .map_err(|err| match err {
+                cassowary::AddConstraintError::UnsatisfiableConstraint => {
+                    GuiError::UnsatisfiableConstraint
+                }
+                // Each call creates a new solver constraint, so duplicates can't happen.
+                cassowary::AddConstraintError::DuplicateConstraint
+                | cassowary::AddConstraintError::InternalSolverError(_) => {
+                    panic!("constraint solver error: {:?}", err)
+                }
+            })?;
+        Ok(ConstraintHandle(solver_constraint))
+    }
+
+    pub fn remove_constraint(&self, handle: &ConstraintHandle) -> Result<(), GuiError> {
+        self.state
+            .borrow_mut()
+            .solver
+            .remove_constraint(&handle.0)
+            .map_err(|_| GuiError::UnknownConstraint)
+    }
+
+    /// Forgets the variables of a child that was removed from the container, along with its
+    /// minimum size constraints. Constraints added with `add_constraint` that refer to it should be
+    /// removed first.
+    pub fn forget_slot(&self, slot_id: SlotId) {
+        let mut state = self.state.borrow_mut();
+        if let Some((_, constraints)) = state.minimum_sizes.remove(&slot_id) {
+            for constraint in &constraints {
+                state.solver.remove_constraint(constraint).unwrap();
+            }
+        }
+        state.slots.remove(&slot_id);
+    }
+
+    /// Solves for the current container size and children's minimum sizes, and positions the
+    /// visible children accordingly.
+    pub fn layout_children<C: Context>(&self, args: &mut LayoutChildrenArgs<'_, C>) {
+        let mut state = self.state.borrow_mut();
+        let bounds = args.slots.get(args.slot_id).bounds;
+        let (container_width, container_height) = (state.container_width, state.container_height);
+        // Both are edit variables, added in `new`.
+        state.solver.suggest_value(container_width, from_scalar(bounds.size.width)).unwrap();
+        state.solver.suggest_value(container_height, from_scalar(bounds.size.height)).unwrap();
+
+        let children = crate::visible_children(args.slots, args.slot_id).collect::<Vec<_>>();
+        for &child_id in &children {
+            state.update_minimum_size(child_id, args.minimum_size_cache[&child_id]);
+        }
+        for child_id in children {
+            let variables = state.slot_variables(child_id);
+            let value = |variable| to_scalar(state.solver.get_value(variable));
+            let child = args.slots.get_mut(child_id);
+            child.bounds.x = value(variables.left);
+            child.bounds.y = value(variables.top);
+            child.bounds.size = Dimensions::new(
+                value(variables.width).max(scalar::ZERO),
+                value(variables.height).max(scalar::ZERO),
+            );
+        }
+    }
+}
+
+impl Default for ConstraintLayout {
+    fn default() -> ConstraintLayout {
+        ConstraintLayout::new()
+    }
+}
+
+#[cfg(not(feature = "scalar_i32"))]
+fn to_scalar(value: f64) -> Scalar {
+    value
+}
+
+#[cfg(feature = "scalar_i32")]
+fn to_scalar(value: f64) -> Scalar {
+    value.round() as Scalar
+}
+
+#[cfg(not(feature = "scalar_i32"))]
+fn from_scalar(value: Scalar) -> f64 {
+    value
+}
+
+#[cfg(feature = "scalar_i32")]
+fn from_scalar(value: Scalar) -> f64 {
+    f64::from(value)
+}
@@ -0,0 +1,104 @@
+//! Keeps its children at a fixed aspect ratio, centred within its bounds.
+
+use crate::{
+    scalar, Bounds, Context, Dimensions, DrawArgs, LayoutChildrenArgs, MinimumSizeArgs,
+    ProcessEventArgs, ProcessEventResult, Scalar, Widget,
+};
+
+/// How children are sized when the container's bounds don't match the ratio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AspectMode {
+    /// The largest size at the ratio that fits inside the bounds, leaving space on two sides.
+    Fit,
+    /// The smallest size at the ratio that covers the bounds, overflowing on two sides.
+    /// `AspectRatioContainer` clips its children in this mode.
+    Cover,
+}
+
+/// The largest of the visible children's minimum sizes. The ratio is applied at layout, so it
+/// doesn't make the container any larger.
+pub fn minimum_size<C: Context>(args: &MinimumSizeArgs<'_, C>) -> Dimensions {
+    super::center::minimum_size(args)
+}
+
+/// Lays children out at `ratio` (width divided by height) according to `mode`, but never below
+/// their minimum size.
+pub fn layout_children<C: Context>(
+    args: &mut LayoutChildrenArgs<'_, C>,
+    ratio: f32,
+    mode: AspectMode,
+) {
+    let size = args.slots.get(args.slot_id).bounds.size;
+    let width_for_height = (size.height as f32 * ratio) as Scalar;
+    let height_for_width = (size.width as f32 / ratio) as Scalar;
+    // Fitting keeps whichever dimension would otherwise overflow; covering keeps the other.
+    let fit_width = width_for_height <= size.width;
+    let ratio_size = if ratio <= 0.0 {
+        size
+    } else if fit_width == (mode == AspectMode::Fit) {
+        Dimensions::new(width_for_height, size.height)
+    } else {
+        Dimensions::new(size.width, height_for_width)
+    };
+
+    let children = crate::visible_children(args.slots, args.slot_id).collect::<Vec<_>>();
+    for child_id in children {
+        let child_minimum_size = args.minimum_size_cache[&child_id];
+        let child_size = Dimensions::new(
+            ratio_size.width.max(child_minimum_size.width),
+            ratio_size.height.max(child_minimum_size.height),
+        );
+        args.slots.get_mut(child_id).bounds = Bounds::new(
+            (size.width - child_size.width) / scalar::TWO,
+            (size.height - child_size.height) / scalar::TWO,
+            child_size.width,
+            child_size.height,
+        );
+    }
+}
+
+/// A container that keeps its children at `ratio` (width divided by height).
+#[derive(Debug, Clone)]
+pub struct AspectRatioContainer {
+    pub ratio: f32,
+    pub mode: AspectMode,
+}
+
+impl AspectRatioContainer {
+    pub fn new(ratio: f32, mode: AspectMode) -> AspectRatioContainer {
+        AspectRatioContainer { ratio, mode }
+    }
+}
+
+impl<C: Context> Widget<C> for AspectRatioContainer {
+    fn kind_id(&self) -> &'static str {
+        "AspectRatioContainer"
+    }
+
+    fn takes_focus(&self) -> bool {
+        false
+    }
+
+    fn minimum_size(&self, args: MinimumSizeArgs<'_, C>) -> Dimensions {
+        minimum_size(&args)
+    }
+
+    fn layout_children(&self, mut args: LayoutChildrenArgs<'_, C>) {
+        layout_children(&mut args, self.ratio, self.mode)
+    }
+
+    fn process_event(&mut self, _args: ProcessEventArgs<'_, C>) -> ProcessEventResult {
+        ProcessEventResult::default()
+    }
+
+    fn draw(&self, _args: DrawArgs<'_, C>) {}
+
+    fn clip_rect(&self, size: Dimensions) -> Option<Bounds> {
+        match self.mode {
+            AspectMode::Fit => None,
+            AspectMode::Cover => {
+                Some(Bounds::new(scalar::ZERO, scalar::ZERO, size.width, size.height))
+            }
+        }
+    }
+}
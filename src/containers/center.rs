@@ -0,0 +1,58 @@
+//! Centres its children at their minimum size.
+
+use crate::{
+    scalar, Bounds, Context, Dimensions, DrawArgs, LayoutChildrenArgs, MinimumSizeArgs,
+    ProcessEventArgs, ProcessEventResult, Widget,
+};
+
+pub fn minimum_size<C: Context>(args: &MinimumSizeArgs<'_, C>) -> Dimensions {
+    crate::visible_children(args.slots, args.slot_id).fold(Dimensions::zero(), |size, child_id| {
+        let child_minimum_size = args.minimum_size_cache[&child_id];
+        Dimensions::new(
+            size.width.max(child_minimum_size.width),
+            size.height.max(child_minimum_size.height),
+        )
+    })
+}
+
+pub fn layout_children<C: Context>(args: &mut LayoutChildrenArgs<'_, C>) {
+    let size = args.slots.get(args.slot_id).bounds.size;
+    let children = crate::visible_children(args.slots, args.slot_id).collect::<Vec<_>>();
+    for child_id in children {
+        let child_size = args.minimum_size_cache[&child_id];
+        args.slots.get_mut(child_id).bounds = Bounds::new(
+            (size.width - child_size.width) / scalar::TWO,
+            (size.height - child_size.height) / scalar::TWO,
+            child_size.width,
+            child_size.height,
+        );
+    }
+}
+
+/// A container that centres its children at their minimum size.
+#[derive(Debug, Clone, Default)]
+pub struct CenterContainer;
+
+impl<C: Context> Widget<C> for CenterContainer {
+    fn kind_id(&self) -> &'static str {
+        "CenterContainer"
+    }
+
+    fn takes_focus(&self) -> bool {
+        false
+    }
+
+    fn minimum_size(&self, args: MinimumSizeArgs<'_, C>) -> Dimensions {
+        minimum_size(&args)
+    }
+
+    fn layout_children(&self, mut args: LayoutChildrenArgs<'_, C>) {
+        layout_children(&mut args)
+    }
+
+    fn process_event(&mut self, _args: ProcessEventArgs<'_, C>) -> ProcessEventResult {
+        ProcessEventResult::default()
+    }
+
+    fn draw(&self, _args: DrawArgs<'_, C>) {}
+}
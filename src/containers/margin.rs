@@ -0,0 +1,157 @@
+//! Insets its children from its edges by the `margin_left`, `margin_right`, `margin_top` and
+//! `margin_bottom` style fields.
+
+use std::cell::Cell;
+
+use super::StyleScalar;
+use crate::{
+    scalar, Bounds, Context, Dimensions, DrawArgs, LayoutChildrenArgs, MinimumSizeArgs,
+    ProcessEventArgs, ProcessEventResult, Scalar, SlotStyle, Widget,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Insets {
+    pub left: Scalar,
+    pub right: Scalar,
+    pub top: Scalar,
+    pub bottom: Scalar,
+}
+
+impl Insets {
+    pub fn zero() -> Insets {
+        Insets {
+            left: scalar::ZERO,
+            right: scalar::ZERO,
+            top: scalar::ZERO,
+            bottom: scalar::ZERO,
+        }
+    }
+
+    /// Reads the insets from the `margin_*` style fields. Missing fields (or a missing style)
+    /// count as zero.
+    pub fn from_style<R, S: StyleScalar>(style: &SlotStyle<'_, R, S>) -> Insets {
+        let field = |name| {
+            style
+                .try_get_field(name)
+                .ok()
+                .and_then(StyleScalar::to_scalar)
+                .unwrap_or(scalar::ZERO)
+        };
+        Insets {
+            left: field("margin_left"),
+            right: field("margin_right"),
+            top: field("margin_top"),
+            bottom: field("margin_bottom"),
+        }
+    }
+
+    fn horizontal(&self) -> Scalar {
+        self.left + self.right
+    }
+
+    fn vertical(&self) -> Scalar {
+        self.top + self.bottom
+    }
+}
+
+pub fn minimum_size<C: Context>(args: &MinimumSizeArgs<'_, C>, insets: &Insets) -> Dimensions {
+    let content_size = crate::visible_children(args.slots, args.slot_id).fold(
+        Dimensions::zero(),
+        |size, child_id| {
+            let child_minimum_size = args.minimum_size_cache[&child_id];
+            Dimensions::new(
+                size.width.max(child_minimum_size.width),
+                size.height.max(child_minimum_size.height),
+            )
+        },
+    );
+    Dimensions::new(
+        content_size.width + insets.horizontal(),
+        content_size.height + insets.vertical(),
+    )
+}
+
+/// Like `minimum_size`, but measures children against `available` space less the insets (see
+/// `Widget::measure`).
+pub fn measure<C: Context>(
+    args: &MinimumSizeArgs<'_, C>,
+    available: Dimensions,
+    insets: &Insets,
+) -> Dimensions {
+    let minimum_size = minimum_size(args, insets);
+    let available = Dimensions::new(
+        available.width - insets.horizontal(),
+        available.height - insets.vertical(),
+    );
+    let height = crate::visible_children(args.slots, args.slot_id)
+        .map(|child_id| args.measure(child_id, available).height)
+        .fold(scalar::ZERO, Scalar::max);
+    Dimensions::new(minimum_size.width, height + insets.vertical())
+}
+
+pub fn layout_children<C: Context>(args: &mut LayoutChildrenArgs<'_, C>, insets: &Insets) {
+    let size = args.slots.get(args.slot_id).bounds.size;
+    let children = crate::visible_children(args.slots, args.slot_id).collect::<Vec<_>>();
+    for child_id in children {
+        let child_minimum_size = args.minimum_size_cache[&child_id];
+        args.slots.get_mut(child_id).bounds = Bounds::new(
+            insets.left,
+            insets.top,
+            (size.width - insets.horizontal()).max(child_minimum_size.width),
+            (size.height - insets.vertical()).max(child_minimum_size.height),
+        );
+    }
+}
+
+/// A container that insets its children by the `margin_*` fields of its style.
+pub struct MarginContainer {
+    /// The insets read by the last `minimum_size`, since layout has no access to the style.
+    insets: Cell<Insets>,
+}
+
+impl MarginContainer {
+    pub fn new() -> MarginContainer {
+        MarginContainer {
+            insets: Cell::new(Insets::zero()),
+        }
+    }
+}
+
+impl Default for MarginContainer {
+    fn default() -> MarginContainer {
+        MarginContainer::new()
+    }
+}
+
+impl<C: Context> Widget<C> for MarginContainer
+where
+    C::StyleFieldValue: StyleScalar,
+{
+    fn kind_id(&self) -> &'static str {
+        "MarginContainer"
+    }
+
+    fn takes_focus(&self) -> bool {
+        false
+    }
+
+    fn minimum_size(&self, args: MinimumSizeArgs<'_, C>) -> Dimensions {
+        let insets = Insets::from_style(args.style);
+        self.insets.set(insets);
+        minimum_size(&args, &insets)
+    }
+
+    fn measure(&self, args: MinimumSizeArgs<'_, C>, available: Dimensions) -> Dimensions {
+        measure(&args, available, &Insets::from_style(args.style))
+    }
+
+    fn layout_children(&self, mut args: LayoutChildrenArgs<'_, C>) {
+        layout_children(&mut args, &self.insets.get())
+    }
+
+    fn process_event(&mut self, _args: ProcessEventArgs<'_, C>) -> ProcessEventResult {
+        ProcessEventResult::default()
+    }
+
+    fn draw(&self, _args: DrawArgs<'_, C>) {}
+}
@@ -0,0 +1,33 @@
+//! Simple single-purpose containers, each available as `minimum_size`/`layout_children` helpers
+//! for use in custom widgets and as a ready-made `Widget`.
+//!
+//! Like the layouts, every container arranges all of its visible children the same way, so they
+//! normally have a single child.
+
+pub mod aspect_ratio;
+pub mod center;
+pub mod margin;
+
+pub use self::aspect_ratio::{AspectMode, AspectRatioContainer};
+pub use self::center::CenterContainer;
+pub use self::margin::{Insets, MarginContainer};
+
+use crate::Scalar;
+
+/// Style field values that can be read as a `Scalar`, for containers that take sizes from the
+/// theme (such as `MarginContainer`).
+pub trait StyleScalar {
+    fn to_scalar(&self) -> Option<Scalar>;
+}
+
+impl StyleScalar for Scalar {
+    fn to_scalar(&self) -> Option<Scalar> {
+        Some(*self)
+    }
+}
+
+impl StyleScalar for f32 {
+    fn to_scalar(&self) -> Option<Scalar> {
+        Some(*self as Scalar)
+    }
+}
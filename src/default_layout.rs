@@ -2,7 +2,10 @@
 
 use std::collections::HashMap;
 
-use crate::{scalar, Scalar, Dimensions, Context, LayoutChildrenArgs, MinimumSizeArgs};
+use crate::{
+    scalar, Scalar, Dimensions, Context, LayoutChildrenArgs, MinimumSizeArgs, SlotId, SlotInfo,
+    Slots,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Axis {
@@ -10,15 +13,74 @@ pub enum Axis {
     Vertical,
 }
 
+/// How children are positioned along the layout axis when they don't fill it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MainAxisAlignment {
+    Start,
+    Center,
+    End,
+    /// Leftover space is split evenly between children, with none before the first or after the
+    /// last.
+    SpaceBetween,
+    /// Leftover space is split evenly around each child, so the gaps at either end are half the
+    /// size of the gaps between children.
+    SpaceAround,
+}
+
+/// How children are positioned perpendicular to the layout axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossAxisAlignment {
+    Start,
+    Center,
+    End,
+    /// Children fill the cross axis, as if they were set to expand on it.
+    Stretch,
+}
+
+/// Per-slot settings for `default_layout` (also read by `flex_layout`), stored in
+/// `SlotInfo::layout_data`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ItemSettings {
+    /// Overrides the layout's cross axis alignment for this slot alone.
+    pub cross_axis_alignment: Option<CrossAxisAlignment>,
+}
+
+/// The cross axis alignment of the slot with `info`, in a layout that aligns children to
+/// `default` unless they override it.
+pub(super) fn cross_axis_alignment_of(
+    info: &SlotInfo,
+    default: CrossAxisAlignment,
+) -> CrossAxisAlignment {
+    info.layout_data
+        .get::<ItemSettings>()
+        .and_then(|item| item.cross_axis_alignment)
+        .unwrap_or(default)
+}
+
 #[derive(Debug, Clone)]
 pub struct Settings {
     pub axis: Axis,
     pub padding: Scalar,
     pub child_spacing: Scalar,
+    pub main_axis_alignment: MainAxisAlignment,
+    /// Used for children whose `ItemSettings` don't override it.
+    pub cross_axis_alignment: CrossAxisAlignment,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            axis: Axis::Horizontal,
+            padding: scalar::ZERO,
+            child_spacing: scalar::ZERO,
+            main_axis_alignment: MainAxisAlignment::Start,
+            cross_axis_alignment: CrossAxisAlignment::Start,
+        }
+    }
 }
 
 pub fn minimum_size<C: Context>(args: &MinimumSizeArgs<'_, C>, settings: &Settings) -> Dimensions {
-    let Settings { axis, padding, child_spacing } = *settings;
+    let Settings { axis, padding, child_spacing, .. } = *settings;
     
     let mut minimum_size = Dimensions::zero();
     let num_children = crate::visible_children(args.slots, args.slot_id).count();
@@ -59,102 +121,356 @@ pub fn minimum_size<C: Context>(args: &MinimumSizeArgs<'_, C>, settings: &Settin
     minimum_size
 }
 
-pub fn layout_children<C: Context>(args: &mut LayoutChildrenArgs<'_, C>, settings: &Settings) {
-    let Settings { axis, padding, child_spacing } = *settings;
-    
-    let bounds = args.slots.get(args.slot_id).bounds;
+/// Like `minimum_size`, but measures children against `available` space (see
+/// `Widget::measure`), so children whose height depends on their width are accounted for.
+pub fn measure<C: Context>(
+    args: &MinimumSizeArgs<'_, C>,
+    available: Dimensions,
+    settings: &Settings,
+) -> Dimensions {
+    let Settings { axis, padding, child_spacing, cross_axis_alignment, .. } = *settings;
+
+    let minimum_size = minimum_size(args, settings);
     let children = crate::visible_children(args.slots, args.slot_id).collect::<Vec<_>>();
-    let num_children = children.len();
-    let size = match axis {
-        Axis::Horizontal => bounds.size.width,
-        Axis::Vertical => bounds.size.height,
-    };
-    let available_size = size - padding * scalar::TWO - child_spacing * ((num_children - 1) as Scalar);
+    if children.is_empty() {
+        return minimum_size;
+    }
+    let spacing = child_spacing * ((children.len() - 1) as Scalar);
+    match axis {
+        Axis::Horizontal => {
+            // Measure each child at the width it will be given.
+            let available_height = available.height - padding * scalar::TWO;
+            let main_sizes = main_axis_sizes(
+                args.slots,
+                &children,
+                axis,
+                available.width - padding * scalar::TWO - spacing,
+                args.minimum_size_cache,
+            );
+            let height = children
+                .iter()
+                .zip(main_sizes)
+                .map(|(&child_id, width)| {
+                    args.measure(child_id, Dimensions::new(width, available_height)).height
+                })
+                .fold(scalar::ZERO, Scalar::max);
+            Dimensions::new(minimum_size.width, height + padding * scalar::TWO)
+        }
+        Axis::Vertical => {
+            // Measure each child at the width it will be given, then stack the heights.
+            let cross_size = available.width - padding * scalar::TWO;
+            let height = children.iter().fold(scalar::ZERO, |height, &child_id| {
+                let width = cross_axis_size(
+                    args.slots,
+                    child_id,
+                    axis,
+                    cross_axis_alignment,
+                    cross_size,
+                    args.minimum_size_cache[&child_id].width,
+                );
+                let available = Dimensions::new(width, available.height - padding * scalar::TWO);
+                height + args.measure(child_id, available).height
+            });
+            Dimensions::new(minimum_size.width, height + spacing + padding * scalar::TWO)
+        }
+    }
+}
 
+/// Distributes `available_size` along the axis between `children`, returning each child's size.
+fn main_axis_sizes(
+    slots: &Slots,
+    children: &[SlotId],
+    axis: Axis,
+    available_size: Scalar,
+    minimum_sizes: &HashMap<SlotId, Dimensions>,
+) -> Vec<Scalar> {
     let mut irregular_sizes = HashMap::new();
-    let mut num_regular_children = num_children;
+    let mut regular_stretch_ratio = 0.0;
     let mut reserved_size = scalar::ZERO;
 
     let mut expand_children = Vec::new();
     // Children not set as `expand` will always be their minimum size.
     for child_id in children.iter().rev() {
-        let child = args.slots.get(*child_id);
-        let child_minimum_size = args.minimum_size_cache[child_id];
+        let child = slots.get(*child_id);
+        let child_minimum_size = minimum_sizes[child_id];
         let (axis_expand, minimum_size) = match axis {
             Axis::Horizontal => (child.info.expand_x, child_minimum_size.width),
             Axis::Vertical => (child.info.expand_y, child_minimum_size.height),
         };
         if !axis_expand {
             irregular_sizes.insert(*child_id, minimum_size);
-            num_regular_children -= 1;
             reserved_size += minimum_size;
         } else {
-            expand_children.push((*child_id, minimum_size));
+            let stretch_ratio = child.info.stretch_ratio.max(0.0);
+            regular_stretch_ratio += stretch_ratio;
+            expand_children.push((*child_id, minimum_size, stretch_ratio));
         }
     }
 
-    // Children set as `expand` will be at least their minimum size or at most the "regular" size.
-    // (The leftover size that is distributed equally (for now) between children marked `expand`.)
-    // We need to figure out which children will be irregularly-sized.
-    //
-    // It makes sense to check this on children ordered from largest to smallest minimum sizes.
-    expand_children.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap());
-    for (child_id, minimum_size) in expand_children.into_iter().rev() {
-        let regular_size = if num_regular_children > 0 {
-            (available_size - reserved_size) / num_regular_children as Scalar
+    // The leftover size is distributed between children marked `expand`, weighted by their
+    // stretch ratios. A child whose share would be less than its minimum size is irregularly-sized
+    // instead, which shrinks the leftover size for everyone else.
+    let regular_size = |leftover_size: Scalar, total_stretch_ratio: f32, stretch_ratio: f32| {
+        if total_stretch_ratio > 0.0 {
+            scale(leftover_size, stretch_ratio, total_stretch_ratio)
         } else {
             scalar::ZERO
-        };
-        if minimum_size > regular_size {
+        }
+    };
+
+    // It makes sense to check this on children ordered from largest to smallest minimum size per
+    // unit of stretch ratio.
+    let size_per_ratio = |size: Scalar, stretch_ratio: f32| {
+        if stretch_ratio > 0.0 {
+            size as f32 / stretch_ratio
+        } else {
+            f32::INFINITY
+        }
+    };
+    expand_children.sort_by(|&(_, a, a_ratio), &(_, b, b_ratio)| {
+        size_per_ratio(a, a_ratio)
+            .partial_cmp(&size_per_ratio(b, b_ratio))
+            .unwrap()
+    });
+    let mut regular_children = Vec::new();
+    for (child_id, minimum_size, stretch_ratio) in expand_children.into_iter().rev() {
+        let size = regular_size(
+            available_size - reserved_size,
+            regular_stretch_ratio,
+            stretch_ratio,
+        );
+        if stretch_ratio == 0.0 || minimum_size > size {
             irregular_sizes.insert(child_id, minimum_size);
-            num_regular_children -= 1;
+            regular_stretch_ratio -= stretch_ratio;
             reserved_size += minimum_size;
+        } else {
+            regular_children.push((child_id, minimum_size, stretch_ratio));
         }
     }
 
-    let regular_size = if num_regular_children > 0 {
-        (available_size - reserved_size) / num_regular_children as Scalar
+    // Likewise, a child whose share would be more than its maximum size is capped at it, which
+    // grows the leftover size for everyone else. This is checked from the smallest maximum size
+    // per unit of stretch ratio, and can't push anyone else below their minimum size.
+    let mut capped_children = regular_children
+        .into_iter()
+        .filter_map(|(child_id, minimum_size, stretch_ratio)| {
+            let maximum_size = slots.get(child_id).info.maximum_size?;
+            let maximum_size = match axis {
+                Axis::Horizontal => maximum_size.width,
+                Axis::Vertical => maximum_size.height,
+            };
+            Some((child_id, maximum_size.max(minimum_size), stretch_ratio))
+        })
+        .collect::<Vec<_>>();
+    capped_children.sort_by(|&(_, a, a_ratio), &(_, b, b_ratio)| {
+        size_per_ratio(a, a_ratio)
+            .partial_cmp(&size_per_ratio(b, b_ratio))
+            .unwrap()
+    });
+    for (child_id, maximum_size, stretch_ratio) in capped_children {
+        let size = regular_size(
+            available_size - reserved_size,
+            regular_stretch_ratio,
+            stretch_ratio,
+        );
+        if maximum_size < size {
+            irregular_sizes.insert(child_id, maximum_size);
+            regular_stretch_ratio -= stretch_ratio;
+            reserved_size += maximum_size;
+        }
+    }
+
+    let leftover_size = available_size - reserved_size;
+    children
+        .iter()
+        .map(|child_id| {
+            let child = slots.get(*child_id);
+            let child_minimum_size = minimum_sizes[child_id];
+            let child_size = irregular_sizes
+                .get(child_id)
+                .cloned()
+                .unwrap_or_else(|| {
+                    regular_size(leftover_size, regular_stretch_ratio, child.info.stretch_ratio)
+                });
+            match axis {
+                Axis::Horizontal => child_size.max(child_minimum_size.width),
+                Axis::Vertical => child_size.max(child_minimum_size.height),
+            }
+        })
+        .collect()
+}
+
+/// `size * numerator / denominator`, without losing `size`'s precision to `f32`.
+#[cfg(not(feature = "scalar_i32"))]
+fn scale(size: Scalar, numerator: f32, denominator: f32) -> Scalar {
+    size * Scalar::from(numerator) / Scalar::from(denominator)
+}
+
+#[cfg(feature = "scalar_i32")]
+fn scale(size: Scalar, numerator: f32, denominator: f32) -> Scalar {
+    (f64::from(size) * f64::from(numerator) / f64::from(denominator)) as Scalar
+}
+
+/// The size of a child across the axis: all of `cross_size` (up to its maximum size) if it expands
+/// or stretches on that axis, otherwise its minimum size.
+fn cross_axis_size(
+    slots: &Slots,
+    child_id: SlotId,
+    axis: Axis,
+    cross_axis_alignment: CrossAxisAlignment,
+    cross_size: Scalar,
+    minimum_cross_size: Scalar,
+) -> Scalar {
+    let info = &slots.get(child_id).info;
+    let (other_expand, maximum_cross_size) = match axis {
+        Axis::Horizontal => (info.expand_y, info.maximum_size.map(|size| size.height)),
+        Axis::Vertical => (info.expand_x, info.maximum_size.map(|size| size.width)),
+    };
+    let alignment = cross_axis_alignment_of(info, cross_axis_alignment);
+    if other_expand || alignment == CrossAxisAlignment::Stretch {
+        let cross_size = maximum_cross_size.map_or(cross_size, |maximum| cross_size.min(maximum));
+        cross_size.max(minimum_cross_size)
     } else {
-        scalar::ZERO
+        minimum_cross_size
+    }
+}
+
+pub fn layout_children<C: Context>(args: &mut LayoutChildrenArgs<'_, C>, settings: &Settings) {
+    let Settings {
+        axis,
+        padding,
+        child_spacing,
+        main_axis_alignment,
+        cross_axis_alignment,
+    } = *settings;
+    
+    let bounds = args.slots.get(args.slot_id).bounds;
+    let children = crate::visible_children(args.slots, args.slot_id).collect::<Vec<_>>();
+    if children.is_empty() {
+        return;
+    }
+    let num_children = children.len();
+    let size = match axis {
+        Axis::Horizontal => bounds.size.width,
+        Axis::Vertical => bounds.size.height,
     };
-    let mut offset = padding;
-    for (i, child_id) in children.iter().enumerate() {
+    let available_size = size - padding * scalar::TWO - child_spacing * ((num_children - 1) as Scalar);
+    let cross_size = match axis {
+        Axis::Horizontal => bounds.size.height,
+        Axis::Vertical => bounds.size.width,
+    } - padding * scalar::TWO;
+
+    // In a vertical layout each child's width is known up front, so children can be measured
+    // before distributing heights. In a horizontal layout, heights are measured once the widths
+    // have been distributed below.
+    let minimum_sizes = children
+        .iter()
+        .map(|&child_id| {
+            let minimum_size = args.minimum_size_cache[&child_id];
+            let minimum_size = match axis {
+                Axis::Horizontal => minimum_size,
+                Axis::Vertical => {
+                    let width = cross_axis_size(
+                        args.slots,
+                        child_id,
+                        axis,
+                        cross_axis_alignment,
+                        cross_size,
+                        minimum_size.width,
+                    );
+                    let measured_size =
+                        args.measure(child_id, Dimensions::new(width, available_size));
+                    Dimensions::new(minimum_size.width, measured_size.height)
+                }
+            };
+            (child_id, minimum_size)
+        })
+        .collect::<HashMap<_, _>>();
+    let main_sizes = main_axis_sizes(args.slots, &children, axis, available_size, &minimum_sizes);
+
+    // Children are measured across the axis once their size along it is known, then shrunk to
+    // fit their aspect ratio (if any).
+    let sizes = children
+        .iter()
+        .zip(main_sizes)
+        .map(|(&child_id, main_size)| {
+            let child_minimum_cross_size = match axis {
+                Axis::Horizontal => {
+                    args.measure(child_id, Dimensions::new(main_size, cross_size)).height
+                }
+                Axis::Vertical => minimum_sizes[&child_id].width,
+            };
+            let child_cross_size = cross_axis_size(
+                args.slots,
+                child_id,
+                axis,
+                cross_axis_alignment,
+                cross_size,
+                child_minimum_cross_size,
+            );
+            let (size, minimum_size) = match axis {
+                Axis::Horizontal => (
+                    Dimensions::new(main_size, child_cross_size),
+                    Dimensions::new(minimum_sizes[&child_id].width, child_minimum_cross_size),
+                ),
+                Axis::Vertical => (
+                    Dimensions::new(child_cross_size, main_size),
+                    Dimensions::new(child_minimum_cross_size, minimum_sizes[&child_id].height),
+                ),
+            };
+            let size = args.slots.get(child_id).info.constrain_size(size, minimum_size);
+            match axis {
+                Axis::Horizontal => (size.width, size.height),
+                Axis::Vertical => (size.height, size.width),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // Any space still unused (because no child expands to take it) is distributed according to
+    // the main axis alignment.
+    let free_size = sizes
+        .iter()
+        .fold(available_size, |free_size, &(main_size, _)| free_size - main_size)
+        .max(scalar::ZERO);
+    let (mut offset, extra_spacing) = match main_axis_alignment {
+        MainAxisAlignment::Start => (padding, scalar::ZERO),
+        MainAxisAlignment::Center => (padding + free_size / scalar::TWO, scalar::ZERO),
+        MainAxisAlignment::End => (padding + free_size, scalar::ZERO),
+        MainAxisAlignment::SpaceBetween if num_children > 1 => {
+            (padding, free_size / (num_children - 1) as Scalar)
+        }
+        MainAxisAlignment::SpaceBetween => (padding, scalar::ZERO),
+        MainAxisAlignment::SpaceAround => {
+            let gap = free_size / num_children as Scalar;
+            (padding + gap / scalar::TWO, gap)
+        }
+    };
+
+    for (i, (child_id, (main_size, child_cross_size))) in children.iter().zip(sizes).enumerate() {
         let child = args.slots.get_mut(*child_id);
-        let other_expand = match axis {
-            Axis::Horizontal => child.info.expand_y,
-            Axis::Vertical => child.info.expand_x,
+        let alignment = cross_axis_alignment_of(&child.info, cross_axis_alignment);
+        let cross_offset = padding + match alignment {
+            CrossAxisAlignment::Start | CrossAxisAlignment::Stretch => scalar::ZERO,
+            CrossAxisAlignment::Center => (cross_size - child_cross_size) / scalar::TWO,
+            CrossAxisAlignment::End => cross_size - child_cross_size,
         };
-        let child_size = irregular_sizes
-            .get(child_id)
-            .cloned()
-            .unwrap_or(regular_size);
-        let child_minimum_size = args.minimum_size_cache[child_id];
         match axis {
             Axis::Horizontal => {
                 child.bounds.x = offset;
-                child.bounds.y = padding;
-                child.bounds.size.width = child_size.max(child_minimum_size.width);
-                child.bounds.size.height = if other_expand {
-                    (bounds.size.height - (padding * scalar::TWO)).max(child_minimum_size.height)
-                } else {
-                    child_minimum_size.height
-                };
-                offset += child.bounds.size.width;
+                child.bounds.y = cross_offset;
+                child.bounds.size.width = main_size;
+                child.bounds.size.height = child_cross_size;
             }
             Axis::Vertical => {
-                child.bounds.x = padding;
+                child.bounds.x = cross_offset;
                 child.bounds.y = offset;
-                child.bounds.size.width = if other_expand {
-                    (bounds.size.width - (padding * scalar::TWO)).max(child_minimum_size.width)
-                } else {
-                    child_minimum_size.width
-                };
-                child.bounds.size.height = child_size.max(child_minimum_size.height);
-                offset += child.bounds.size.height;
+                child.bounds.size.width = child_cross_size;
+                child.bounds.size.height = main_size;
             }
         }
+        offset += main_size;
         if i < children.len() - 1 {
-            offset += child_spacing;
+            offset += child_spacing + extra_spacing;
         }
     }
 }
@@ -0,0 +1,175 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::{SlotId, WidgetId};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuiError {
+    /// The slot id was never handed out by this `Gui`.
+    UnknownSlot(SlotId),
+    /// The slot the id referred to has been removed.
+    StaleSlot(SlotId),
+    /// The widget id was never handed out by this `Gui`.
+    UnknownWidget(WidgetId),
+    /// The widget the id referred to has been removed.
+    StaleWidget(WidgetId),
+    /// The widget exists, but is not of the requested type.
+    WrongWidgetType {
+        expected: &'static str,
+        found_kind_id: &'static str,
+    },
+    /// The operation is not permitted on the root slot.
+    RootSlot,
+    /// Moving the slot would make it its own ancestor.
+    CyclicMove {
+        slot_id: SlotId,
+        new_parent_id: SlotId,
+    },
+    /// The slot has no widget bound to it.
+    NoWidget(SlotId),
+    /// Slot names may not be empty, contain `/`, start with `#`, or be `.` or `..`.
+    InvalidName(String),
+    /// A sibling slot already uses this name. `parent_id` is `None` for slots in a `Template`,
+    /// which have no ids until instantiated.
+    DuplicateName {
+        parent_id: Option<SlotId>,
+        name: String,
+    },
+    /// No slot could be found at this path.
+    NoSlotAtPath(String),
+    /// A child index was past the end of the parent's children.
+    ChildIndexOutOfRange {
+        parent_id: SlotId,
+        index: usize,
+    },
+    /// No constructor is registered for this widget kind.
+    UnknownWidgetKind(String),
+    /// A widget property required by a constructor is missing.
+    MissingProperty {
+        key: String,
+    },
+    /// A widget property's value could not be parsed.
+    InvalidProperty {
+        key: String,
+        value: String,
+    },
+    /// A style override's value could not be parsed.
+    InvalidStyleValue {
+        field: String,
+        value: String,
+    },
+    /// Scene text was malformed. `line` is 1-based, or 0 if the error concerns the whole scene.
+    SceneParse {
+        line: usize,
+        message: String,
+    },
+    /// Theme style text was malformed. `line` is 1-based.
+    ThemeParse {
+        line: usize,
+        message: String,
+    },
+    /// The signal has no field with this name, or the field has a different type.
+    MissingSignalField {
+        signal: String,
+        field: String,
+    },
+    /// The theme has no style for this widget kind.
+    MissingStyle {
+        kind_id: String,
+    },
+    /// Neither the slot's overrides nor the theme's style for the widget kind define this field.
+    MissingStyleField {
+        kind_id: String,
+        field: String,
+    },
+    /// A required layout constraint conflicts with the required constraints already added.
+    UnsatisfiableConstraint,
+    /// The layout constraint was never added, or has already been removed.
+    UnknownConstraint,
+    /// The widget doesn't support cloning through `Widget::as_clone_widget`.
+    NotCloneable {
+        kind_id: String,
+    },
+}
+
+impl fmt::Display for GuiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            GuiError::UnknownSlot(slot_id) => write!(f, "unknown slot: {:?}", slot_id),
+            GuiError::StaleSlot(slot_id) => write!(f, "slot has been removed: {:?}", slot_id),
+            GuiError::UnknownWidget(widget_id) => write!(f, "unknown widget: {:?}", widget_id),
+            GuiError::StaleWidget(widget_id) => {
+                write!(f, "widget has been removed: {:?}", widget_id)
+            }
+            GuiError::WrongWidgetType { expected, found_kind_id } => write!(
+                f,
+                "expected widget of type `{}`, found widget of kind `{}`",
+                expected,
+                found_kind_id,
+            ),
+            GuiError::RootSlot => write!(f, "operation not permitted on the root slot"),
+            GuiError::CyclicMove { slot_id, new_parent_id } => write!(
+                f,
+                "cannot move slot {:?} into its own subtree (new parent {:?})",
+                slot_id,
+                new_parent_id,
+            ),
+            GuiError::NoWidget(slot_id) => write!(f, "slot has no widget: {:?}", slot_id),
+            GuiError::InvalidName(ref name) => write!(f, "invalid slot name: `{}`", name),
+            GuiError::DuplicateName { parent_id: Some(parent_id), ref name } => write!(
+                f,
+                "slot {:?} already has a child named `{}`",
+                parent_id,
+                name,
+            ),
+            GuiError::DuplicateName { parent_id: None, ref name } => {
+                write!(f, "template slot already has a child named `{}`", name)
+            }
+            GuiError::NoSlotAtPath(ref path) => write!(f, "no slot at path: `{}`", path),
+            GuiError::ChildIndexOutOfRange { parent_id, index } => write!(
+                f,
+                "child index {} out of range for slot {:?}",
+                index,
+                parent_id,
+            ),
+            GuiError::UnknownWidgetKind(ref kind_id) => {
+                write!(f, "unknown widget kind: `{}`", kind_id)
+            }
+            GuiError::MissingProperty { ref key } => write!(f, "missing widget property `{}`", key),
+            GuiError::InvalidProperty { ref key, ref value } => {
+                write!(f, "invalid value for widget property `{}`: `{}`", key, value)
+            }
+            GuiError::InvalidStyleValue { ref field, ref value } => {
+                write!(f, "invalid value for style field `{}`: `{}`", field, value)
+            }
+            GuiError::SceneParse { line, ref message } => {
+                write!(f, "scene parse error on line {}: {}", line, message)
+            }
+            GuiError::ThemeParse { line, ref message } => {
+                write!(f, "theme parse error on line {}: {}", line, message)
+            }
+            GuiError::MissingSignalField { ref signal, ref field } => write!(
+                f,
+                "signal `{}` has no field `{}` of the requested type",
+                signal,
+                field,
+            ),
+            GuiError::MissingStyle { ref kind_id } => {
+                write!(f, "theme missing style for widget: `{}`", kind_id)
+            }
+            GuiError::MissingStyleField { ref kind_id, ref field } => write!(
+                f,
+                "style for widget `{}` missing field: `{}`",
+                kind_id,
+                field,
+            ),
+            GuiError::UnsatisfiableConstraint => write!(f, "unsatisfiable layout constraint"),
+            GuiError::UnknownConstraint => write!(f, "unknown layout constraint"),
+            GuiError::NotCloneable { ref kind_id } => {
+                write!(f, "widget can't be cloned: `{}`", kind_id)
+            }
+        }
+    }
+}
+
+impl Error for GuiError {}
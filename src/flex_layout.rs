@@ -0,0 +1,464 @@
+//! A layout modelled on CSS flexbox.
+//!
+//! Children are placed in lines along the main axis (given by `Settings::direction`), optionally
+//! wrapping onto new lines. Each child's `FlexItem` describes how it grows and shrinks to fill its
+//! line, and its `default_layout::ItemSettings` cross axis alignment acts like `align-self`.
+//!
+//! Wrapping flex containers measure against the size they were given by the previous layout (see
+//! `MinimumSizeArgs::available`) in `minimum_size`, and against the offered size in `measure`.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::default_layout::{cross_axis_alignment_of, Axis, CrossAxisAlignment, MainAxisAlignment};
+use crate::{
+    scalar, Scalar, Dimensions, Context, LayoutChildrenArgs, MinimumSizeArgs, SlotId, SlotInfo,
+    Slots,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Row,
+    RowReverse,
+    Column,
+    ColumnReverse,
+}
+
+impl Direction {
+    fn axis(self) -> Axis {
+        match self {
+            Direction::Row | Direction::RowReverse => Axis::Horizontal,
+            Direction::Column | Direction::ColumnReverse => Axis::Vertical,
+        }
+    }
+
+    fn is_reverse(self) -> bool {
+        self == Direction::RowReverse || self == Direction::ColumnReverse
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wrap {
+    /// All children share a single line, shrinking if they don't fit.
+    NoWrap,
+    /// Children wrap onto new lines after the first, stacked in the cross direction.
+    Wrap,
+    /// Like `Wrap`, but lines are stacked in the opposite cross direction.
+    WrapReverse,
+}
+
+/// The flex properties of a single child, stored in its `SlotInfo::layout_data`. Children without
+/// one use `FlexItem::default()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlexItem {
+    /// How much of a line's free space this child takes, relative to its siblings.
+    pub grow: f32,
+    /// How much this child gives up when its line overflows, relative to its siblings (and
+    /// weighted by its basis). Children never shrink below their minimum size.
+    pub shrink: f32,
+    /// The child's size along the main axis before growing or shrinking. `None` uses its minimum
+    /// size.
+    pub basis: Option<Scalar>,
+}
+
+impl Default for FlexItem {
+    fn default() -> FlexItem {
+        FlexItem {
+            grow: 0.0,
+            shrink: 1.0,
+            basis: None,
+        }
+    }
+}
+
+fn flex_item(info: &SlotInfo) -> FlexItem {
+    info.layout_data.get().copied().unwrap_or_default()
+}
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub direction: Direction,
+    pub wrap: Wrap,
+    pub padding: Scalar,
+    /// Horizontal spacing between neighbouring children (or columns, when wrapping vertically).
+    pub column_gap: Scalar,
+    /// Vertical spacing between neighbouring children (or rows, when wrapping horizontally).
+    pub row_gap: Scalar,
+    /// How each line's leftover space is distributed along the main axis.
+    pub justify_content: MainAxisAlignment,
+    /// Used for children whose `default_layout::ItemSettings` don't override it.
+    pub align_items: CrossAxisAlignment,
+}
+
+impl Settings {
+    fn gaps(&self) -> (Scalar, Scalar) {
+        match self.direction.axis() {
+            Axis::Horizontal => (self.column_gap, self.row_gap),
+            Axis::Vertical => (self.row_gap, self.column_gap),
+        }
+    }
+}
+
+fn main_and_cross(axis: Axis, dimensions: Dimensions) -> (Scalar, Scalar) {
+    match axis {
+        Axis::Horizontal => (dimensions.width, dimensions.height),
+        Axis::Vertical => (dimensions.height, dimensions.width),
+    }
+}
+
+fn from_main_and_cross(axis: Axis, main: Scalar, cross: Scalar) -> Dimensions {
+    match axis {
+        Axis::Horizontal => Dimensions::new(main, cross),
+        Axis::Vertical => Dimensions::new(cross, main),
+    }
+}
+
+fn spaced_total(sizes: impl Iterator<Item = Scalar>, gap: Scalar) -> Scalar {
+    let mut count = 0;
+    let total = sizes.fold(scalar::ZERO, |total, size| {
+        count += 1;
+        total + size
+    });
+    if count > 0 {
+        total + gap * ((count - 1) as Scalar)
+    } else {
+        total
+    }
+}
+
+struct Item {
+    slot_id: SlotId,
+    flex: FlexItem,
+    alignment: CrossAxisAlignment,
+    /// The flex base size, never less than `minimum_main`.
+    base: Scalar,
+    minimum_main: Scalar,
+    minimum_cross: Scalar,
+    /// The resolved main size.
+    main: Scalar,
+}
+
+/// Children arranged into lines, with resolved main sizes.
+struct Plan {
+    items: Vec<Item>,
+    lines: Vec<Range<usize>>,
+}
+
+impl Plan {
+    /// `measure` is used to find children's minimum main size in a vertical layout, where it may
+    /// depend on the width they are given.
+    fn new<M>(
+        slots: &Slots,
+        minimum_size_cache: &HashMap<SlotId, Dimensions>,
+        slot_id: SlotId,
+        settings: &Settings,
+        available: Dimensions,
+        measure: M,
+    ) -> Plan
+    where
+        M: Fn(SlotId, Dimensions) -> Dimensions,
+    {
+        let axis = settings.direction.axis();
+        let (main_gap, _) = settings.gaps();
+        let (available_main, available_cross) = main_and_cross(axis, available);
+
+        let items = crate::visible_children(slots, slot_id)
+            .map(|child_id| {
+                let info = &slots.get(child_id).info;
+                let alignment = cross_axis_alignment_of(info, settings.align_items);
+                let (mut minimum_main, minimum_cross) =
+                    main_and_cross(axis, minimum_size_cache[&child_id]);
+                if axis == Axis::Vertical {
+                    let width = if alignment == CrossAxisAlignment::Stretch
+                        && settings.wrap == Wrap::NoWrap
+                    {
+                        available_cross.max(minimum_cross)
+                    } else {
+                        minimum_cross
+                    };
+                    minimum_main = measure(child_id, Dimensions::new(width, available_main)).height;
+                }
+                let flex = flex_item(info);
+                let base = flex.basis.unwrap_or(minimum_main).max(minimum_main);
+                Item {
+                    slot_id: child_id,
+                    flex,
+                    alignment,
+                    base,
+                    minimum_main,
+                    minimum_cross,
+                    main: base,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut lines: Vec<Range<usize>> = Vec::new();
+        let mut line_main = scalar::ZERO;
+        for (i, item) in items.iter().enumerate() {
+            match lines.last_mut() {
+                Some(line)
+                    if settings.wrap == Wrap::NoWrap
+                        || line_main + main_gap + item.base <= available_main =>
+                {
+                    line.end = i + 1;
+                    line_main += main_gap + item.base;
+                }
+                _ => {
+                    lines.push(i..i + 1);
+                    line_main = item.base;
+                }
+            }
+        }
+
+        let mut plan = Plan { items, lines };
+        for line in plan.lines.clone() {
+            resolve_flexible_lengths(&mut plan.items[line], available_main, main_gap);
+        }
+        plan
+    }
+}
+
+/// Grows or shrinks `items` to fill `available_main`, following the CSS flexbox algorithm: items
+/// that would violate their minimum size are frozen at it, and the rest are flexed again.
+fn resolve_flexible_lengths(items: &mut [Item], available_main: Scalar, gap: Scalar) {
+    let gaps = gap * (items.len().saturating_sub(1) as Scalar);
+    let growing = spaced_total(items.iter().map(|item| item.base), gap) < available_main;
+    let factor = |item: &Item| {
+        if growing {
+            item.flex.grow.max(0.0)
+        } else {
+            item.flex.shrink.max(0.0) * item.base as f32
+        }
+    };
+    let mut frozen = items.iter().map(|item| factor(item) == 0.0).collect::<Vec<_>>();
+    for item in items.iter_mut() {
+        item.main = item.base;
+    }
+
+    while frozen.iter().any(|&frozen| !frozen) {
+        // Frozen items keep their size; the rest start again from their base size.
+        let free_main = items.iter().zip(&frozen).fold(
+            available_main - gaps,
+            |free_main, (item, &frozen)| free_main - if frozen { item.main } else { item.base },
+        );
+        let total_factor = items
+            .iter()
+            .zip(&frozen)
+            .filter(|&(_, &frozen)| !frozen)
+            .map(|(item, _)| factor(item))
+            .sum::<f32>();
+
+        let mut violated = false;
+        for (item, frozen) in items.iter_mut().zip(frozen.iter_mut()) {
+            if *frozen {
+                continue;
+            }
+            let share = (free_main as f32 * factor(item) / total_factor) as Scalar;
+            item.main = item.base + share;
+            if item.main < item.minimum_main {
+                item.main = item.minimum_main;
+                *frozen = true;
+                violated = true;
+            }
+        }
+        if !violated {
+            break;
+        }
+    }
+}
+
+pub fn minimum_size<C: Context>(args: &MinimumSizeArgs<'_, C>, settings: &Settings) -> Dimensions {
+    let axis = settings.direction.axis();
+    let (main_gap, cross_gap) = settings.gaps();
+    let children = crate::visible_children(args.slots, args.slot_id).collect::<Vec<_>>();
+    // Children that can't shrink need room for their whole basis.
+    let minimum_main = |child_id: &SlotId| {
+        let flex = flex_item(&args.slots.get(*child_id).info);
+        let (minimum_main, _) = main_and_cross(axis, args.minimum_size_cache[child_id]);
+        if flex.shrink > 0.0 {
+            minimum_main
+        } else {
+            flex.basis.unwrap_or(minimum_main).max(minimum_main)
+        }
+    };
+    let minimum_cross = |child_id: &SlotId| main_and_cross(axis, args.minimum_size_cache[child_id]).1;
+
+    let (main, cross) = match settings.wrap {
+        Wrap::NoWrap => (
+            spaced_total(children.iter().map(minimum_main), main_gap),
+            children.iter().map(minimum_cross).fold(scalar::ZERO, Scalar::max),
+        ),
+        Wrap::Wrap | Wrap::WrapReverse => {
+            let available = inner_size(args.available(), settings.padding);
+            let plan = Plan::new(
+                args.slots,
+                args.minimum_size_cache,
+                args.slot_id,
+                settings,
+                available,
+                |child_id, _| args.minimum_size_cache[&child_id],
+            );
+            let line_crosses = plan.lines.iter().map(|line| {
+                plan.items[line.clone()]
+                    .iter()
+                    .map(|item| item.minimum_cross)
+                    .fold(scalar::ZERO, Scalar::max)
+            });
+            (
+                children.iter().map(minimum_main).fold(scalar::ZERO, Scalar::max),
+                spaced_total(line_crosses, cross_gap),
+            )
+        }
+    };
+    let padding = settings.padding * scalar::TWO;
+    let size = from_main_and_cross(axis, main, cross);
+    Dimensions::new(size.width + padding, size.height + padding)
+}
+
+/// Like `minimum_size`, but lays children out within `available` space and measures them (see
+/// `Widget::measure`), so children whose height depends on their width are accounted for.
+pub fn measure<C: Context>(
+    args: &MinimumSizeArgs<'_, C>,
+    available: Dimensions,
+    settings: &Settings,
+) -> Dimensions {
+    let axis = settings.direction.axis();
+    let (_, cross_gap) = settings.gaps();
+    let minimum_size = minimum_size(args, settings);
+    let available = inner_size(available, settings.padding);
+    let plan = Plan::new(
+        args.slots,
+        args.minimum_size_cache,
+        args.slot_id,
+        settings,
+        available,
+        |child_id, available| args.measure(child_id, available),
+    );
+    let (_, available_cross) = main_and_cross(axis, available);
+    let line_crosses = plan
+        .lines
+        .iter()
+        .map(|line| line_cross_size(&plan.items[line.clone()], axis, available_cross, |id, a| {
+            args.measure(id, a)
+        }));
+    let cross = spaced_total(line_crosses, cross_gap) + settings.padding * scalar::TWO;
+    match axis {
+        Axis::Horizontal => Dimensions::new(minimum_size.width, cross),
+        Axis::Vertical => Dimensions::new(cross, minimum_size.height),
+    }
+}
+
+fn inner_size(size: Dimensions, padding: Scalar) -> Dimensions {
+    Dimensions::new(
+        (size.width - padding * scalar::TWO).max(scalar::ZERO),
+        (size.height - padding * scalar::TWO).max(scalar::ZERO),
+    )
+}
+
+/// The cross size of an item once its main size is known. In a horizontal layout, its height
+/// may depend on its resolved width.
+fn item_cross_size<M>(item: &Item, axis: Axis, available_cross: Scalar, measure: &M) -> Scalar
+where
+    M: Fn(SlotId, Dimensions) -> Dimensions,
+{
+    match axis {
+        Axis::Horizontal => measure(item.slot_id, Dimensions::new(item.main, available_cross)).height,
+        Axis::Vertical => item.minimum_cross,
+    }
+}
+
+fn line_cross_size<M>(items: &[Item], axis: Axis, available_cross: Scalar, measure: M) -> Scalar
+where
+    M: Fn(SlotId, Dimensions) -> Dimensions,
+{
+    items
+        .iter()
+        .map(|item| item_cross_size(item, axis, available_cross, &measure))
+        .fold(scalar::ZERO, Scalar::max)
+}
+
+pub fn layout_children<C: Context>(args: &mut LayoutChildrenArgs<'_, C>, settings: &Settings) {
+    let axis = settings.direction.axis();
+    let (main_gap, cross_gap) = settings.gaps();
+    let padding = settings.padding;
+
+    let bounds = args.slots.get(args.slot_id).bounds;
+    let available = inner_size(bounds.size, padding);
+    let (available_main, available_cross) = main_and_cross(axis, available);
+    let plan = Plan::new(
+        args.slots,
+        args.minimum_size_cache,
+        args.slot_id,
+        settings,
+        available,
+        |child_id, available| args.measure(child_id, available),
+    );
+
+    // A single line fills the container's cross size; wrapped lines are sized to their content.
+    let line_crosses = plan
+        .lines
+        .iter()
+        .map(|line| match settings.wrap {
+            Wrap::NoWrap => available_cross,
+            Wrap::Wrap | Wrap::WrapReverse => {
+                line_cross_size(&plan.items[line.clone()], axis, available_cross, |id, a| {
+                    args.measure(id, a)
+                })
+            }
+        })
+        .collect::<Vec<_>>();
+    let mut placements = Vec::with_capacity(plan.items.len());
+    let mut cross_offset = scalar::ZERO;
+    for (line, &line_cross) in plan.lines.iter().zip(&line_crosses) {
+        let items = &plan.items[line.clone()];
+        let free_main = (available_main
+            - spaced_total(items.iter().map(|item| item.main), main_gap))
+            .max(scalar::ZERO);
+        let num_items = items.len();
+        let (mut main_offset, extra_gap) = match settings.justify_content {
+            MainAxisAlignment::Start => (scalar::ZERO, scalar::ZERO),
+            MainAxisAlignment::Center => (free_main / scalar::TWO, scalar::ZERO),
+            MainAxisAlignment::End => (free_main, scalar::ZERO),
+            MainAxisAlignment::SpaceBetween if num_items > 1 => {
+                (scalar::ZERO, free_main / (num_items - 1) as Scalar)
+            }
+            MainAxisAlignment::SpaceBetween => (scalar::ZERO, scalar::ZERO),
+            MainAxisAlignment::SpaceAround => {
+                let gap = free_main / num_items as Scalar;
+                (gap / scalar::TWO, gap)
+            }
+        };
+        for item in items {
+            let item_cross = if item.alignment == CrossAxisAlignment::Stretch {
+                line_cross.max(item.minimum_cross)
+            } else {
+                item_cross_size(item, axis, available_cross, &|id, a| args.measure(id, a))
+            };
+            let item_cross_offset = cross_offset + match item.alignment {
+                CrossAxisAlignment::Start | CrossAxisAlignment::Stretch => scalar::ZERO,
+                CrossAxisAlignment::Center => (line_cross - item_cross) / scalar::TWO,
+                CrossAxisAlignment::End => line_cross - item_cross,
+            };
+            placements.push((item.slot_id, main_offset, item.main, item_cross_offset, item_cross));
+            main_offset += item.main + main_gap + extra_gap;
+        }
+        cross_offset += line_cross + cross_gap;
+    }
+
+    for (slot_id, mut main_offset, main, mut cross_offset, cross) in placements {
+        if settings.direction.is_reverse() {
+            main_offset = available_main - main_offset - main;
+        }
+        if settings.wrap == Wrap::WrapReverse {
+            cross_offset = available_cross - cross_offset - cross;
+        }
+        let child = args.slots.get_mut(slot_id);
+        let (x, y) = match axis {
+            Axis::Horizontal => (main_offset, cross_offset),
+            Axis::Vertical => (cross_offset, main_offset),
+        };
+        child.bounds.x = padding + x;
+        child.bounds.y = padding + y;
+        child.bounds.size = from_main_and_cross(axis, main, cross);
+    }
+}
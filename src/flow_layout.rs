@@ -0,0 +1,167 @@
+//! A layout that places children in lines, wrapping to a new line when the current one runs out
+//! of space.
+//!
+//! Since where lines wrap depends on the slot's size, `minimum_size` measures against the size
+//! the slot was given by the previous layout (see `MinimumSizeArgs::available`), while `measure`
+//! measures against the size a parent layout offers.
+
+use std::collections::HashMap;
+
+use crate::default_layout::{Axis, MainAxisAlignment};
+use crate::{scalar, Scalar, Dimensions, Context, LayoutChildrenArgs, MinimumSizeArgs, SlotId};
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// The direction children are placed in within a line. `Horizontal` fills rows left to right,
+    /// then top to bottom; `Vertical` fills columns top to bottom, then left to right.
+    pub axis: Axis,
+    pub padding: Scalar,
+    /// Spacing between neighbouring children in a line.
+    pub item_spacing: Scalar,
+    /// Spacing between neighbouring lines.
+    pub line_spacing: Scalar,
+    /// How the children of each line are positioned within it.
+    pub line_alignment: MainAxisAlignment,
+}
+
+/// A run of consecutive children that fit on one line.
+struct Line {
+    start: usize,
+    end: usize,
+    /// The sum of the children's sizes along the axis, including spacing between them.
+    main_size: Scalar,
+    /// The largest of the children's sizes across the axis.
+    cross_size: Scalar,
+}
+
+fn main_and_cross(axis: Axis, dimensions: Dimensions) -> (Scalar, Scalar) {
+    match axis {
+        Axis::Horizontal => (dimensions.width, dimensions.height),
+        Axis::Vertical => (dimensions.height, dimensions.width),
+    }
+}
+
+/// Breaks `children` into lines no longer than `line_size`. A child that is longer than
+/// `line_size` on its own gets a line to itself.
+fn break_lines(
+    children: &[SlotId],
+    minimum_size_cache: &HashMap<SlotId, Dimensions>,
+    settings: &Settings,
+    line_size: Scalar,
+) -> Vec<Line> {
+    let mut lines: Vec<Line> = Vec::new();
+    for (i, child_id) in children.iter().enumerate() {
+        let (main_size, cross_size) = main_and_cross(settings.axis, minimum_size_cache[child_id]);
+        match lines.last_mut() {
+            Some(line) if line.main_size + settings.item_spacing + main_size <= line_size => {
+                line.end = i + 1;
+                line.main_size += settings.item_spacing + main_size;
+                line.cross_size = line.cross_size.max(cross_size);
+            }
+            _ => lines.push(Line {
+                start: i,
+                end: i + 1,
+                main_size,
+                cross_size,
+            }),
+        }
+    }
+    lines
+}
+
+pub fn minimum_size<C: Context>(args: &MinimumSizeArgs<'_, C>, settings: &Settings) -> Dimensions {
+    let (available_main_size, _) = main_and_cross(settings.axis, args.available());
+    size_for(args, settings, available_main_size)
+}
+
+/// Like `minimum_size`, but wraps lines to fit `available` (see `Widget::measure`) rather than
+/// the size given by the previous layout.
+pub fn measure<C: Context>(
+    args: &MinimumSizeArgs<'_, C>,
+    available: Dimensions,
+    settings: &Settings,
+) -> Dimensions {
+    let (available_main_size, _) = main_and_cross(settings.axis, available);
+    size_for(args, settings, available_main_size)
+}
+
+/// The minimum size when lines wrap at `available_main_size`.
+fn size_for<C: Context>(
+    args: &MinimumSizeArgs<'_, C>,
+    settings: &Settings,
+    available_main_size: Scalar,
+) -> Dimensions {
+    let Settings { axis, padding, line_spacing, .. } = *settings;
+
+    let children = crate::visible_children(args.slots, args.slot_id).collect::<Vec<_>>();
+    let lines = break_lines(
+        &children,
+        args.minimum_size_cache,
+        settings,
+        available_main_size - padding * scalar::TWO,
+    );
+
+    // Along the axis we only need room for the longest child, since everything else can wrap.
+    let main_size = children
+        .iter()
+        .map(|child_id| main_and_cross(axis, args.minimum_size_cache[child_id]).0)
+        .fold(scalar::ZERO, Scalar::max);
+    let mut cross_size = lines
+        .iter()
+        .fold(scalar::ZERO, |cross_size, line| cross_size + line.cross_size);
+    if !lines.is_empty() {
+        cross_size += line_spacing * ((lines.len() - 1) as Scalar);
+    }
+    let (width, height) = match axis {
+        Axis::Horizontal => (main_size, cross_size),
+        Axis::Vertical => (cross_size, main_size),
+    };
+    Dimensions::new(width + padding * scalar::TWO, height + padding * scalar::TWO)
+}
+
+pub fn layout_children<C: Context>(args: &mut LayoutChildrenArgs<'_, C>, settings: &Settings) {
+    let Settings { axis, padding, item_spacing, line_spacing, line_alignment } = *settings;
+
+    let bounds = args.slots.get(args.slot_id).bounds;
+    let children = crate::visible_children(args.slots, args.slot_id).collect::<Vec<_>>();
+    let (line_size, _) = main_and_cross(axis, bounds.size);
+    let line_size = line_size - padding * scalar::TWO;
+    let lines = break_lines(&children, args.minimum_size_cache, settings, line_size);
+
+    let mut cross_offset = padding;
+    for line in lines {
+        let num_children = line.end - line.start;
+        let free_size = (line_size - line.main_size).max(scalar::ZERO);
+        let (mut main_offset, extra_spacing) = match line_alignment {
+            MainAxisAlignment::Start => (padding, scalar::ZERO),
+            MainAxisAlignment::Center => (padding + free_size / scalar::TWO, scalar::ZERO),
+            MainAxisAlignment::End => (padding + free_size, scalar::ZERO),
+            MainAxisAlignment::SpaceBetween if num_children > 1 => {
+                (padding, free_size / (num_children - 1) as Scalar)
+            }
+            MainAxisAlignment::SpaceBetween => (padding, scalar::ZERO),
+            MainAxisAlignment::SpaceAround => {
+                let gap = free_size / num_children as Scalar;
+                (padding + gap / scalar::TWO, gap)
+            }
+        };
+        for child_id in &children[line.start..line.end] {
+            let child_minimum_size = args.minimum_size_cache[child_id];
+            let (main_size, _) = main_and_cross(axis, child_minimum_size);
+            let child = args.slots.get_mut(*child_id);
+            child.bounds.size = child_minimum_size;
+            match axis {
+                Axis::Horizontal => {
+                    child.bounds.x = main_offset;
+                    child.bounds.y = cross_offset;
+                }
+                Axis::Vertical => {
+                    child.bounds.x = cross_offset;
+                    child.bounds.y = main_offset;
+                }
+            }
+            main_offset += main_size + item_spacing + extra_spacing;
+        }
+        cross_offset += line.cross_size + line_spacing;
+    }
+}
@@ -0,0 +1,141 @@
+//! A layout that arranges children into rows with a fixed number of columns.
+
+use std::collections::HashMap;
+
+use crate::{scalar, Scalar, Dimensions, Context, LayoutChildrenArgs, MinimumSizeArgs, SlotId, Slots};
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// The number of columns. Children fill rows from left to right, starting a new row after
+    /// every `columns` children. Treated as 1 if zero.
+    pub columns: usize,
+    pub padding: Scalar,
+    pub horizontal_spacing: Scalar,
+    pub vertical_spacing: Scalar,
+}
+
+/// Per-column widths and per-row heights, along with which columns and rows expand.
+struct Tracks {
+    column_widths: Vec<Scalar>,
+    column_expand: Vec<bool>,
+    row_heights: Vec<Scalar>,
+    row_expand: Vec<bool>,
+}
+
+impl Tracks {
+    /// Each column is as wide as its widest child and each row as tall as its tallest child. A
+    /// column (or row) expands if any of its children has `expand_x` (or `expand_y`) set.
+    fn measure(
+        slots: &Slots,
+        children: &[SlotId],
+        minimum_size_cache: &HashMap<SlotId, Dimensions>,
+        columns: usize,
+    ) -> Tracks {
+        let num_columns = columns.min(children.len());
+        // `usize::div_ceil` needs Rust 1.73.
+        #[allow(clippy::manual_div_ceil)]
+        let num_rows = (children.len() + columns - 1) / columns;
+        let mut tracks = Tracks {
+            column_widths: vec![scalar::ZERO; num_columns],
+            column_expand: vec![false; num_columns],
+            row_heights: vec![scalar::ZERO; num_rows],
+            row_expand: vec![false; num_rows],
+        };
+        for (i, child_id) in children.iter().enumerate() {
+            let (column, row) = (i % columns, i / columns);
+            let info = &slots.get(*child_id).info;
+            let child_minimum_size = minimum_size_cache[child_id];
+            tracks.column_widths[column] = tracks.column_widths[column].max(child_minimum_size.width);
+            tracks.row_heights[row] = tracks.row_heights[row].max(child_minimum_size.height);
+            tracks.column_expand[column] |= info.expand_x;
+            tracks.row_expand[row] |= info.expand_y;
+        }
+        tracks
+    }
+}
+
+/// The total size of `sizes` laid out with `spacing` between each.
+fn spaced_total(sizes: &[Scalar], spacing: Scalar) -> Scalar {
+    let total = sizes.iter().fold(scalar::ZERO, |total, &size| total + size);
+    if sizes.is_empty() {
+        total
+    } else {
+        total + spacing * ((sizes.len() - 1) as Scalar)
+    }
+}
+
+/// Distributes leftover space (if any) equally between the expanding tracks.
+fn expand_tracks(sizes: &mut [Scalar], expand: &[bool], available_size: Scalar, spacing: Scalar) {
+    let num_expanding = expand.iter().filter(|&&expand| expand).count();
+    if num_expanding == 0 {
+        return;
+    }
+    let leftover_size = (available_size - spaced_total(sizes, spacing)).max(scalar::ZERO);
+    let extra_size = leftover_size / num_expanding as Scalar;
+    for (size, &expand) in sizes.iter_mut().zip(expand) {
+        if expand {
+            *size += extra_size;
+        }
+    }
+}
+
+pub fn minimum_size<C: Context>(args: &MinimumSizeArgs<'_, C>, settings: &Settings) -> Dimensions {
+    let Settings { columns, padding, horizontal_spacing, vertical_spacing } = *settings;
+
+    let children = crate::visible_children(args.slots, args.slot_id).collect::<Vec<_>>();
+    let tracks = Tracks::measure(args.slots, &children, args.minimum_size_cache, columns.max(1));
+    Dimensions::new(
+        spaced_total(&tracks.column_widths, horizontal_spacing) + padding * scalar::TWO,
+        spaced_total(&tracks.row_heights, vertical_spacing) + padding * scalar::TWO,
+    )
+}
+
+pub fn layout_children<C: Context>(args: &mut LayoutChildrenArgs<'_, C>, settings: &Settings) {
+    let Settings { columns, padding, horizontal_spacing, vertical_spacing } = *settings;
+    let columns = columns.max(1);
+
+    let bounds = args.slots.get(args.slot_id).bounds;
+    let children = crate::visible_children(args.slots, args.slot_id).collect::<Vec<_>>();
+    let Tracks {
+        mut column_widths,
+        column_expand,
+        mut row_heights,
+        row_expand,
+    } = Tracks::measure(args.slots, &children, args.minimum_size_cache, columns);
+    expand_tracks(
+        &mut column_widths,
+        &column_expand,
+        bounds.size.width - padding * scalar::TWO,
+        horizontal_spacing,
+    );
+    expand_tracks(
+        &mut row_heights,
+        &row_expand,
+        bounds.size.height - padding * scalar::TWO,
+        vertical_spacing,
+    );
+
+    let mut y = padding;
+    for (row, row_children) in children.chunks(columns).enumerate() {
+        let mut x = padding;
+        for (column, child_id) in row_children.iter().enumerate() {
+            let child_minimum_size = args.minimum_size_cache[child_id];
+            let child = args.slots.get_mut(*child_id);
+            // Children that expand fill their cell; others keep their minimum size.
+            child.bounds.x = x;
+            child.bounds.y = y;
+            child.bounds.size.width = if child.info.expand_x {
+                column_widths[column]
+            } else {
+                child_minimum_size.width
+            };
+            child.bounds.size.height = if child.info.expand_y {
+                row_heights[row]
+            } else {
+                child_minimum_size.height
+            };
+            x += column_widths[column] + horizontal_spacing;
+        }
+        y += row_heights[row] + vertical_spacing;
+    }
+}
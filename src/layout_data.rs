@@ -0,0 +1,97 @@
+//! Per-slot data for particular layouts.
+
+use std::fmt;
+
+/// A value a layout stores on a slot in `SlotInfo::layout_data`. Implemented for every type that
+/// is `Clone`, `PartialEq` and `Debug`.
+pub trait LayoutItem: downcast_rs::Downcast + fmt::Debug {
+    fn clone_item(&self) -> Box<dyn LayoutItem>;
+
+    fn eq_item(&self, other: &dyn LayoutItem) -> bool;
+}
+
+downcast_rs::impl_downcast!(LayoutItem);
+
+impl<T: Clone + PartialEq + fmt::Debug + 'static> LayoutItem for T {
+    fn clone_item(&self) -> Box<dyn LayoutItem> {
+        Box::new(self.clone())
+    }
+
+    fn eq_item(&self, other: &dyn LayoutItem) -> bool {
+        other.downcast_ref::<T>() == Some(self)
+    }
+}
+
+/// Holds at most one value of each `LayoutItem` type, such as `flex_layout::FlexItem`, so that
+/// layouts can keep their own settings on each child slot.
+#[derive(Default)]
+pub struct LayoutData {
+    items: Vec<Box<dyn LayoutItem>>,
+}
+
+impl LayoutData {
+    pub fn new() -> LayoutData {
+        LayoutData::default()
+    }
+
+    /// Returns `self` with `item` set, for use in `SlotInfo` literals.
+    pub fn with<T: LayoutItem>(mut self, item: T) -> LayoutData {
+        self.set(item);
+        self
+    }
+
+    pub fn get<T: LayoutItem>(&self) -> Option<&T> {
+        self.items.iter().find_map(|item| item.downcast_ref())
+    }
+
+    pub fn get_mut<T: LayoutItem>(&mut self) -> Option<&mut T> {
+        self.items.iter_mut().find_map(|item| item.downcast_mut())
+    }
+
+    /// Returns the item of type `T`, inserting `T::default()` first if there is none.
+    pub fn get_or_insert_default<T: LayoutItem + Default>(&mut self) -> &mut T {
+        if self.get::<T>().is_none() {
+            self.items.push(Box::new(T::default()));
+        }
+        self.get_mut().unwrap()
+    }
+
+    /// Stores `item`, replacing any previous item of the same type.
+    pub fn set<T: LayoutItem>(&mut self, item: T) {
+        match self.get_mut() {
+            Some(existing) => *existing = item,
+            None => self.items.push(Box::new(item)),
+        }
+    }
+
+    /// Returns `true` if there was an item of type `T` to remove.
+    pub fn remove<T: LayoutItem>(&mut self) -> bool {
+        let len = self.items.len();
+        self.items.retain(|item| !item.is::<T>());
+        self.items.len() != len
+    }
+}
+
+impl Clone for LayoutData {
+    fn clone(&self) -> LayoutData {
+        LayoutData {
+            items: self.items.iter().map(|item| item.clone_item()).collect(),
+        }
+    }
+}
+
+impl PartialEq for LayoutData {
+    fn eq(&self, other: &LayoutData) -> bool {
+        self.items.len() == other.items.len()
+            && self
+                .items
+                .iter()
+                .all(|item| other.items.iter().any(|other_item| item.eq_item(&**other_item)))
+    }
+}
+
+impl fmt::Debug for LayoutData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.items).finish()
+    }
+}
@@ -1,14 +1,43 @@
 #![warn(rust_2018_idioms)]
 
+mod arena;
+mod builder;
+mod clone;
+#[cfg(feature = "constraint_layout")]
+pub mod constraint_layout;
+pub mod containers;
 pub mod default_layout;
+mod error;
+pub mod flex_layout;
+pub mod flow_layout;
+pub mod grid_layout;
+mod layout_data;
+mod registry;
+mod scene;
+pub mod scroll_container;
+pub mod split_container;
+pub mod tab_container;
 mod theme;
-
+mod template;
+mod traversal;
+
+pub use self::builder::{BuiltTree, TreeBuilder};
+pub use self::clone::CloneWidget;
+pub use self::error::GuiError;
+pub use self::layout_data::{LayoutData, LayoutItem};
+pub use self::registry::{Properties, WidgetRegistry};
+pub use self::scene::Scene;
 pub use self::theme::{SlotStyle, Theme};
+pub use self::template::{Template, TemplateBuilder, WidgetSpec};
+pub use self::traversal::{Ancestors, BreadthFirst, Descendants, PostOrderDescendants, Siblings};
 
 use std::any::Any;
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
 
+use self::arena::{Arena, Index, LookupError};
+
 #[cfg(not(feature = "scalar_i32"))]
 mod scalar {
     pub type Scalar = f64;
@@ -27,7 +56,7 @@ mod scalar {
 
 pub use self::scalar::Scalar;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Dimensions {
     pub width: Scalar,
     pub height: Scalar,
@@ -74,12 +103,97 @@ impl Bounds {
         x > self.x && x < self.x + self.size.width
             && y > self.y && y < self.y + self.size.height
     }
+
+    /// The area covered by both `self` and `other`, which has zero size if they don't overlap.
+    pub fn intersection(&self, other: &Bounds) -> Bounds {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.size.width).min(other.x + other.size.width);
+        let bottom = (self.y + self.size.height).min(other.y + other.size.height);
+        Bounds::new(x, y, (right - x).max(scalar::ZERO), (bottom - y).max(scalar::ZERO))
+    }
+}
+
+/// A pointer (mouse or touch) event, in the same coordinates as `InputEvent::offset_coordinates`
+/// adjusts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PointerEvent {
+    Pressed { x: Scalar, y: Scalar },
+    Moved { x: Scalar, y: Scalar },
+    Released { x: Scalar, y: Scalar },
+    /// A mouse wheel or touchpad scroll. Positive deltas scroll towards the start of the content
+    /// (up or left), as most windowing libraries report them.
+    Scrolled {
+        x: Scalar,
+        y: Scalar,
+        delta_x: Scalar,
+        delta_y: Scalar,
+    },
+}
+
+/// A key, as reported by `InputEvent::key_event`. Keys that no built-in widget responds to can
+/// be reported as `Other`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Tab,
+    Enter,
+    Escape,
+    Space,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Character(char),
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEvent {
+    Pressed { key: Key, modifiers: Modifiers },
+    Released { key: Key, modifiers: Modifiers },
+}
+
+impl PointerEvent {
+    pub fn position(&self) -> (Scalar, Scalar) {
+        match *self {
+            PointerEvent::Pressed { x, y }
+            | PointerEvent::Moved { x, y }
+            | PointerEvent::Released { x, y }
+            | PointerEvent::Scrolled { x, y, .. } => (x, y),
+        }
+    }
 }
 
 pub trait InputEvent {
     fn dirty(&self) -> bool;
     
     fn offset_coordinates(&mut self, x: Scalar, y: Scalar);
+
+    /// Describes this event as a pointer event, for built-in widgets (such as
+    /// `scroll_container::ScrollContainer`) and for keeping pointer events out of clipped areas.
+    /// Defaults to `None`, which treats the event as not having a position.
+    fn pointer_event(&self) -> Option<PointerEvent> {
+        None
+    }
+
+    /// Describes this event as a key event, for built-in widgets (such as
+    /// `tab_container::TabContainer`) that respond to the keyboard. Defaults to `None`.
+    fn key_event(&self) -> Option<KeyEvent> {
+        None
+    }
 }
 
 pub trait Context: 'static {
@@ -88,20 +202,24 @@ pub trait Context: 'static {
     type DrawCommand;
     type DrawContext: Copy;
     type InputEvent: InputEvent;
+
+    /// The draw command that restricts the following draw commands to `clip`, in the same
+    /// coordinates as `DrawArgs::bounds`, or lifts the restriction if `clip` is `None`. `Gui::draw`
+    /// emits one before and after each clipped subtree, with nested clips already intersected.
+    ///
+    /// Defaults to emitting nothing, so contexts that don't support clipping draw unclipped.
+    fn clip_command(_clip: Option<Bounds>) -> Option<Self::DrawCommand> {
+        None
+    }
 }
 
+#[derive(Default)]
 pub struct ProcessEventResult {
     pub request_focus: bool,
     pub signals: Vec<Signal>,
-}
-
-impl Default for ProcessEventResult {
-    fn default() -> ProcessEventResult {
-        ProcessEventResult {
-            request_focus: false,
-            signals: Vec::new(),
-        }
-    }
+    /// Stops the event from reaching the slots that would otherwise get it after this one: the
+    /// slot's ancestors and the siblings beneath it.
+    pub handled: bool,
 }
 
 pub struct Signal {
@@ -129,10 +247,17 @@ impl Signal {
     }
 
     pub fn field<T: 'static>(&self, name: &str) -> &T {
+        self.try_field(name).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_field<T: 'static>(&self, name: &str) -> Result<&T, GuiError> {
         self.fields
             .get(name)
             .and_then(|f| f.downcast_ref())
-            .unwrap()
+            .ok_or_else(|| GuiError::MissingSignalField {
+                signal: self.name.clone(),
+                field: name.to_string(),
+            })
     }
 }
 
@@ -142,14 +267,130 @@ pub struct MinimumSizeArgs<'a, C: Context> {
     pub minimum_size_cache: &'a HashMap<SlotId, Dimensions>,
     pub resources: &'a C::ThemeResources,
     pub style: &'a SlotStyle<'a, C::ThemeResources, C::StyleFieldValue>,
+    available: Dimensions,
+    available_read: Option<&'a Cell<bool>>,
+    measurer: Option<&'a Measurer<'a, C>>,
+}
+
+impl<'a, C: Context> MinimumSizeArgs<'a, C> {
+    /// Arguments for calling `Widget::minimum_size` or `Widget::measure` outside of
+    /// `Gui::layout_if_needed`, e.g. to size a widget by hand. `measure` then returns the slot's
+    /// entry in `minimum_size_cache` (or its `SlotInfo::minimum_size`), and `available` the slot's
+    /// current size.
+    pub fn new(
+        slots: &'a Slots,
+        slot_id: SlotId,
+        minimum_size_cache: &'a HashMap<SlotId, Dimensions>,
+        resources: &'a C::ThemeResources,
+        style: &'a SlotStyle<'a, C::ThemeResources, C::StyleFieldValue>,
+    ) -> MinimumSizeArgs<'a, C> {
+        MinimumSizeArgs {
+            slots,
+            slot_id,
+            minimum_size_cache,
+            resources,
+            style,
+            available: slots.get(slot_id).bounds.size,
+            available_read: None,
+            measurer: None,
+        }
+    }
+
+    /// Measures a slot (normally a child of this one) given `available` space. See
+    /// `Widget::measure`.
+    pub fn measure(&self, slot_id: SlotId, available: Dimensions) -> Dimensions {
+        match self.measurer {
+            Some(measurer) => measurer.measure(self.slots, slot_id, available),
+            None => self
+                .minimum_size_cache
+                .get(&slot_id)
+                .copied()
+                .unwrap_or(self.slots.get(slot_id).info.minimum_size),
+        }
+    }
+
+    /// The size this slot was given by the most recent layout (zero before its first layout), for
+    /// widgets whose minimum size depends on the space they have, like wrapping layouts. Calling
+    /// this makes `Gui::layout_if_needed` lay out again if the slot ends up with a different size.
+    pub fn available(&self) -> Dimensions {
+        if let Some(available_read) = self.available_read {
+            available_read.set(true);
+        }
+        self.available
+    }
 }
 
 pub struct LayoutChildrenArgs<'a, C: Context> {
     pub slots: &'a mut Slots,
     pub slot_id: SlotId,
     pub minimum_size_cache: &'a HashMap<SlotId, Dimensions>,
-    // TODO: Keeping the C parameter right now for future proofing, look into removing
-    phantom: PhantomData<C>,
+    measurer: &'a Measurer<'a, C>,
+}
+
+impl<'a, C: Context> LayoutChildrenArgs<'a, C> {
+    /// Measures a slot (normally a child of this one) given `available` space. See
+    /// `Widget::measure`.
+    pub fn measure(&self, slot_id: SlotId, available: Dimensions) -> Dimensions {
+        self.measurer.measure(self.slots, slot_id, available)
+    }
+}
+
+/// Computes slot sizes on behalf of the minimum size pass and of `measure` calls made during
+/// layout.
+struct Measurer<'a, C: Context> {
+    widgets: &'a Arena<Box<dyn Widget<C>>>,
+    slot_style_overrides: &'a HashMap<SlotId, HashMap<String, C::StyleFieldValue>>,
+    theme: &'a Theme<C::ThemeResources, C::StyleFieldValue>,
+    minimum_size_cache: &'a HashMap<SlotId, Dimensions>,
+}
+
+impl<'a, C: Context> Measurer<'a, C> {
+    fn measure(&self, slots: &Slots, slot_id: SlotId, available: Dimensions) -> Dimensions {
+        // Only the minimum size pass tracks whether the previous layout's size was used.
+        self.size_with(slots, slot_id, None, |widget, args| {
+            widget.measure(args, available)
+        })
+    }
+
+    /// Asks the slot's widget for a size with `size`, which is never smaller than the slot's own
+    /// `SlotInfo::minimum_size`.
+    fn size_with<F>(
+        &self,
+        slots: &Slots,
+        slot_id: SlotId,
+        available_read: Option<&Cell<bool>>,
+        size: F,
+    ) -> Dimensions
+    where
+        F: FnOnce(&dyn Widget<C>, MinimumSizeArgs<'_, C>) -> Dimensions,
+    {
+        let slot = slots.get(slot_id);
+        let widget_id = match slot.widget_id {
+            Some(widget_id) => widget_id,
+            None => return slot.info.minimum_size,
+        };
+        let widget = self.widgets.get(widget_id.0).unwrap();
+        let style = SlotStyle {
+            widget_kind_id: widget.kind_id(),
+            theme: self.theme,
+            field_overrides: self.slot_style_overrides.get(&slot_id),
+        };
+        let args = MinimumSizeArgs {
+            slots,
+            slot_id,
+            minimum_size_cache: self.minimum_size_cache,
+            resources: &self.theme.resources,
+            style: &style,
+            available: slot.bounds.size,
+            available_read,
+            measurer: Some(self),
+        };
+        let widget_size = size(&**widget, args);
+        Dimensions::new(
+            slot.info.minimum_size.width.max(widget_size.width),
+            slot.info.minimum_size.height.max(widget_size.height),
+        )
+    }
 }
 
 pub struct ProcessEventArgs<'a, C: Context> {
@@ -164,6 +405,9 @@ pub struct ProcessEventArgs<'a, C: Context> {
 
 pub struct DrawArgs<'a, C: Context> {
     pub bounds: Bounds,
+    /// The area this widget is clipped to by its ancestors, in the same coordinates as `bounds`,
+    /// or `None` if it isn't clipped. Widgets can use this to skip drawing what won't be seen.
+    pub clip: Option<Bounds>,
     pub focused: bool,
     pub resources: &'a C::ThemeResources,
     pub style: &'a SlotStyle<'a, C::ThemeResources, C::StyleFieldValue>,
@@ -174,16 +418,54 @@ pub struct DrawArgs<'a, C: Context> {
 pub trait Widget<C: Context>: downcast_rs::Downcast {
     fn kind_id(&self) -> &'static str;
 
+    /// Whether `Gui::cycle_focus` can focus this widget's slot. The slot's descendants can be
+    /// focused either way.
     fn takes_focus(&self) -> bool;
 
     /// The minimum size that this widget's slot can shrink to.
     fn minimum_size(&self, args: MinimumSizeArgs<'_, C>) -> Dimensions;
 
+    /// The size this widget needs when given `available` space, for widgets whose height depends
+    /// on their width (or vice versa), like wrapped text. Layouts call this through
+    /// `LayoutChildrenArgs::measure` once they know how much space a child will get, and the
+    /// result may be smaller than `minimum_size` along the dimension that was constrained. Slots
+    /// positioned by their anchors are only measured if their own minimum size, or one in their
+    /// subtree, reads `MinimumSizeArgs::available`.
+    ///
+    /// Defaults to `minimum_size`, ignoring `available`.
+    fn measure(&self, args: MinimumSizeArgs<'_, C>, _available: Dimensions) -> Dimensions {
+        self.minimum_size(args)
+    }
+
     fn layout_children(&self, args: LayoutChildrenArgs<'_, C>);
 
     fn process_event(&mut self, args: ProcessEventArgs<'_, C>) -> ProcessEventResult;
 
     fn draw(&self, args: DrawArgs<'_, C>);
+
+    /// Writes the properties needed to recreate this widget through a `WidgetRegistry`, e.g. when
+    /// saving a scene.
+    fn write_properties(&self, _properties: &mut Properties) {}
+
+    /// Updates this widget in place from `properties`, e.g. when a scene is hot-reloaded, failing
+    /// if any of them are invalid. Widgets that don't override this keep their current state and
+    /// ignore property changes.
+    fn apply_properties(&mut self, _properties: &Properties) -> Result<(), GuiError> {
+        Ok(())
+    }
+
+    /// Opts this widget into being duplicated by `Gui::clone_subtree`. Widgets that implement
+    /// `Clone` can return `Some(self)`.
+    fn as_clone_widget(&self) -> Option<&dyn CloneWidget<C>> {
+        None
+    }
+
+    /// The area, relative to this widget's slot and given the slot's `size`, outside of which its
+    /// descendants aren't drawn (see `Context::clip_command`) and don't receive pointer events.
+    /// Defaults to `None`, leaving descendants unclipped.
+    fn clip_rect(&self, _size: Dimensions) -> Option<Bounds> {
+        None
+    }
 }
 
 downcast_rs::impl_downcast!(Widget<C> where C: Context);
@@ -199,18 +481,45 @@ pub fn visible_children<'a>(
         .cloned()
 }
 
+/// Identifies a slot. Ids are generational: once a slot is removed, its id is never considered
+/// valid again, even if its storage is reused by a new slot.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct SlotId(u32);
+pub struct SlotId(Index);
 
+/// Identifies a widget. Like `SlotId`, widget ids are generational.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct WidgetId(u32);
+pub struct WidgetId(Index);
+
+fn slot_lookup_error(slot_id: SlotId, err: LookupError) -> GuiError {
+    match err {
+        LookupError::Unknown => GuiError::UnknownSlot(slot_id),
+        LookupError::Stale => GuiError::StaleSlot(slot_id),
+    }
+}
+
+fn widget_lookup_error(widget_id: WidgetId, err: LookupError) -> GuiError {
+    match err {
+        LookupError::Unknown => GuiError::UnknownWidget(widget_id),
+        LookupError::Stale => GuiError::StaleWidget(widget_id),
+    }
+}
+
+/// Whether `name` may be used as a slot name: it must be non-empty, may not contain `/` or start
+/// with `#` (which scene paths use for unnamed slots), and may not be `.` or `..`.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.contains('/')
+        && !name.starts_with('#')
+        && name != "."
+        && name != ".."
+}
 
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct WidgetHandle<C: Context, W: Widget<C>>(WidgetId, PhantomData<(C, W)>);
 
 impl<C: Context, W: Widget<C>> Clone for WidgetHandle<C, W> {
     fn clone(&self) -> Self {
-        WidgetHandle(self.0, PhantomData)
+        *self
     }
 }
 
@@ -223,12 +532,27 @@ pub enum GrowDirection {
     Both,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct SlotInfo {
     pub hidden: bool,
+    /// Clips this slot's descendants to its bounds, both when drawing (see
+    /// `Context::clip_command`) and for pointer events.
+    pub clip_contents: bool,
 
     pub minimum_size: Dimensions,
+    /// The largest size this slot is given by the anchor/margin pass and by layouts that support
+    /// it (such as `default_layout`). A larger minimum size takes precedence.
+    pub maximum_size: Option<Dimensions>,
+    /// The width divided by the height this slot is kept at, by shrinking whichever dimension is
+    /// too large. Applied wherever `maximum_size` is, and likewise never below the minimum size.
+    pub aspect_ratio: Option<f32>,
     pub expand_x: bool,
     pub expand_y: bool,
+    /// How much of the leftover space this slot receives relative to its expanding siblings,
+    /// for layouts that distribute it (such as `default_layout`).
+    pub stretch_ratio: f32,
+    /// Settings that particular layouts read for this slot, such as `flex_layout::FlexItem`.
+    pub layout_data: LayoutData,
 
     pub grow_x: GrowDirection,
     pub grow_y: GrowDirection,
@@ -244,13 +568,52 @@ pub struct SlotInfo {
     pub margin_bottom: Scalar,
 }
 
+impl SlotInfo {
+    /// A slot anchored to fill its parent's bounds.
+    pub fn fill() -> SlotInfo {
+        SlotInfo {
+            anchor_right: 1.0,
+            anchor_bottom: 1.0,
+            ..Default::default()
+        }
+    }
+
+    /// Shrinks `size` to fit `maximum_size` and `aspect_ratio`, without going below `minimum_size`
+    /// (normally the slot's calculated minimum size, which already includes
+    /// `SlotInfo::minimum_size`).
+    pub fn constrain_size(&self, size: Dimensions, minimum_size: Dimensions) -> Dimensions {
+        let mut size = size;
+        if let Some(maximum_size) = self.maximum_size {
+            size.width = size.width.min(maximum_size.width);
+            size.height = size.height.min(maximum_size.height);
+        }
+        if let Some(aspect_ratio) = self.aspect_ratio.filter(|&ratio| ratio > 0.0) {
+            let width = (size.height as f32 * aspect_ratio) as Scalar;
+            if width < size.width {
+                size.width = width;
+            } else {
+                size.height = (size.width as f32 / aspect_ratio) as Scalar;
+            }
+        }
+        Dimensions::new(
+            size.width.max(minimum_size.width),
+            size.height.max(minimum_size.height),
+        )
+    }
+}
+
 impl Default for SlotInfo {
     fn default() -> SlotInfo {
         SlotInfo {
             hidden: false,
+            clip_contents: false,
             minimum_size: Dimensions::zero(),
+            maximum_size: None,
+            aspect_ratio: None,
             expand_x: false,
             expand_y: false,
+            stretch_ratio: 1.0,
+            layout_data: LayoutData::new(),
             grow_x: GrowDirection::End,
             grow_y: GrowDirection::End,
             anchor_left: 0.0,
@@ -269,6 +632,7 @@ pub struct Slot {
     pub info: SlotInfo,
     pub bounds: Bounds,
     pub widget_id: Option<WidgetId>,
+    name: Option<String>,
     parent: Option<SlotId>,
     children: Vec<SlotId>,
 }
@@ -277,31 +641,227 @@ impl Slot {
     pub fn children(&self) -> &[SlotId] {
         &self.children
     }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
 }
 
 pub struct Slots {
-    map: HashMap<SlotId, Slot>,
-    next_slot_id: SlotId,
+    arena: Arena<Slot>,
     dirty: bool,
 }
 
 impl Slots {
     pub fn add(&mut self, parent_id: SlotId, info: SlotInfo) -> SlotId {
-        let slot_id = self.next_slot_id;
-        self.next_slot_id.0 += 1;
-        self.map.insert(
-            slot_id,
-            Slot {
-                bounds: Bounds::zero(),
-                parent: Some(parent_id),
-                children: Vec::new(),
-                widget_id: None,
-                info,
-            },
-        );
+        let index = self.get(parent_id).children.len();
+        self.insert_at(parent_id, index, info)
+    }
+
+    /// Adds a new slot as the `index`th child of `parent_id`.
+    ///
+    /// Panics if `index` is greater than the parent's number of children.
+    pub fn insert_at(&mut self, parent_id: SlotId, index: usize, info: SlotInfo) -> SlotId {
+        self.try_insert_at(parent_id, index, info)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_insert_at(
+        &mut self,
+        parent_id: SlotId,
+        index: usize,
+        info: SlotInfo,
+    ) -> Result<SlotId, GuiError> {
+        if index > self.try_get(parent_id)?.children.len() {
+            return Err(GuiError::ChildIndexOutOfRange { parent_id, index });
+        }
+        let slot_id = SlotId(self.arena.insert(Slot {
+            bounds: Bounds::zero(),
+            parent: Some(parent_id),
+            children: Vec::new(),
+            widget_id: None,
+            name: None,
+            info,
+        }));
         let parent_slot = self.get_mut(parent_id);
-        parent_slot.children.push(slot_id);
-        slot_id
+        parent_slot.children.insert(index, slot_id);
+        Ok(slot_id)
+    }
+
+    /// Detaches `slot_id` from its current parent and inserts it as the `index`th child of
+    /// `new_parent_id`. When moving within the same parent, `index` refers to the position after
+    /// the slot has been detached.
+    ///
+    /// Panics if `slot_id` is the root slot, if `new_parent_id` is `slot_id` or one of its
+    /// descendants, or if `index` is out of range.
+    pub fn move_slot(&mut self, slot_id: SlotId, new_parent_id: SlotId, index: usize) {
+        self.try_move_slot(slot_id, new_parent_id, index)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_move_slot(
+        &mut self,
+        slot_id: SlotId,
+        new_parent_id: SlotId,
+        index: usize,
+    ) -> Result<(), GuiError> {
+        let old_parent_id = self.try_get(slot_id)?
+            .parent
+            .ok_or(GuiError::RootSlot)?;
+        let new_parent_slot = self.try_get(new_parent_id)?;
+        let max_index = if new_parent_id == old_parent_id {
+            new_parent_slot.children.len() - 1
+        } else {
+            new_parent_slot.children.len()
+        };
+        if index > max_index {
+            return Err(GuiError::ChildIndexOutOfRange {
+                parent_id: new_parent_id,
+                index,
+            });
+        }
+        if slot_id == new_parent_id || self.is_ancestor_of(slot_id, new_parent_id) {
+            return Err(GuiError::CyclicMove { slot_id, new_parent_id });
+        }
+        if new_parent_id != old_parent_id {
+            if let Some(name) = self.get(slot_id).name() {
+                if self.find_child(new_parent_id, name).is_some() {
+                    return Err(GuiError::DuplicateName {
+                        parent_id: Some(new_parent_id),
+                        name: name.to_string(),
+                    });
+                }
+            }
+        }
+        self.get_mut(old_parent_id)
+            .children
+            .retain(|&child_id| child_id != slot_id);
+        self.get_mut(new_parent_id)
+            .children
+            .insert(index, slot_id);
+        self.get_mut(slot_id).parent = Some(new_parent_id);
+        Ok(())
+    }
+
+    /// Moves `slot_id` to the end of its parent's children, so it is laid out last and drawn on
+    /// top of its siblings.
+    ///
+    /// Panics if `slot_id` is the root slot.
+    pub fn raise_to_top(&mut self, slot_id: SlotId) {
+        self.try_raise_to_top(slot_id)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_raise_to_top(&mut self, slot_id: SlotId) -> Result<(), GuiError> {
+        let parent_id = self.try_get(slot_id)?
+            .parent
+            .ok_or(GuiError::RootSlot)?;
+        let index = self.get(parent_id).children.len() - 1;
+        self.try_move_slot(slot_id, parent_id, index)
+    }
+
+    /// Moves `slot_id` to the start of its parent's children, so it is laid out first and drawn
+    /// beneath its siblings.
+    ///
+    /// Panics if `slot_id` is the root slot.
+    pub fn lower_to_bottom(&mut self, slot_id: SlotId) {
+        self.try_lower_to_bottom(slot_id)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_lower_to_bottom(&mut self, slot_id: SlotId) -> Result<(), GuiError> {
+        let parent_id = self.try_get(slot_id)?
+            .parent
+            .ok_or(GuiError::RootSlot)?;
+        self.try_move_slot(slot_id, parent_id, 0)
+    }
+
+    /// Swaps the positions of the `a`th and `b`th children of `parent_id`.
+    ///
+    /// Panics if either index is out of range.
+    pub fn swap_children(&mut self, parent_id: SlotId, a: usize, b: usize) {
+        self.try_swap_children(parent_id, a, b)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_swap_children(
+        &mut self,
+        parent_id: SlotId,
+        a: usize,
+        b: usize,
+    ) -> Result<(), GuiError> {
+        let children = &mut self.try_get_mut(parent_id)?.children;
+        if let Some(&index) = [a, b].iter().find(|&&index| index >= children.len()) {
+            return Err(GuiError::ChildIndexOutOfRange { parent_id, index });
+        }
+        children.swap(a, b);
+        Ok(())
+    }
+
+    /// Names a slot so it can be found with `find`. Names must be unique among siblings and may
+    /// not contain `/`, start with `#`, or be `.` or `..`.
+    ///
+    /// Panics if the name is invalid or already used by a sibling.
+    pub fn set_name<T: Into<String>>(&mut self, slot_id: SlotId, name: T) {
+        self.try_set_name(slot_id, name)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_set_name<T: Into<String>>(
+        &mut self,
+        slot_id: SlotId,
+        name: T,
+    ) -> Result<(), GuiError> {
+        let name = name.into();
+        if !is_valid_name(&name) {
+            return Err(GuiError::InvalidName(name));
+        }
+        if let Some(parent_id) = self.try_get(slot_id)?.parent {
+            if let Some(sibling_id) = self.find_child(parent_id, &name) {
+                if sibling_id != slot_id {
+                    return Err(GuiError::DuplicateName { parent_id: Some(parent_id), name });
+                }
+            }
+        }
+        self.get_mut(slot_id).name = Some(name);
+        Ok(())
+    }
+
+    pub fn clear_name(&mut self, slot_id: SlotId) {
+        self.get_mut(slot_id).name = None;
+    }
+
+    /// Returns the child of `parent_id` named `name`, if any.
+    pub fn find_child(&self, parent_id: SlotId, name: &str) -> Option<SlotId> {
+        self.get(parent_id)
+            .children
+            .iter()
+            .cloned()
+            .find(|&child_id| self.get(child_id).name() == Some(name))
+    }
+
+    /// Resolves a `/`-separated path of slot names relative to `slot_id`. The segment `..` refers
+    /// to the parent slot and `.` to the current one. Paths starting with `/` are resolved
+    /// relative to the root of the tree instead.
+    pub fn find(&self, slot_id: SlotId, path: &str) -> Result<SlotId, GuiError> {
+        let mut current_slot_id = slot_id;
+        if path.starts_with('/') {
+            while let Some(parent_id) = self.try_get(current_slot_id)?.parent {
+                current_slot_id = parent_id;
+            }
+        }
+        let not_found = || GuiError::NoSlotAtPath(path.to_string());
+        for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+            current_slot_id = match segment {
+                "." => current_slot_id,
+                ".." => self.try_get(current_slot_id)?.parent.ok_or_else(not_found)?,
+                name => {
+                    self.try_get(current_slot_id)?;
+                    self.find_child(current_slot_id, name).ok_or_else(not_found)?
+                }
+            };
+        }
+        Ok(current_slot_id)
     }
 
     pub fn set_size(&mut self, slot_id: SlotId, size: Dimensions) {
@@ -311,12 +871,47 @@ impl Slots {
     }
 
     pub fn get(&self, slot_id: SlotId) -> &Slot {
-        &self.map[&slot_id]
+        self.try_get(slot_id).unwrap_or_else(|err| panic!("{}", err))
     }
 
     pub fn get_mut(&mut self, slot_id: SlotId) -> &mut Slot {
+        self.try_get_mut(slot_id).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_get(&self, slot_id: SlotId) -> Result<&Slot, GuiError> {
+        self.arena
+            .get(slot_id.0)
+            .map_err(|err| slot_lookup_error(slot_id, err))
+    }
+
+    pub fn try_get_mut(&mut self, slot_id: SlotId) -> Result<&mut Slot, GuiError> {
         self.dirty = true;
-        self.map.get_mut(&slot_id).unwrap()
+        self.arena
+            .get_mut(slot_id.0)
+            .map_err(|err| slot_lookup_error(slot_id, err))
+    }
+
+    /// Returns `true` if `slot_id` refers to a slot that has not been removed.
+    pub fn contains(&self, slot_id: SlotId) -> bool {
+        self.arena.contains(slot_id.0)
+    }
+
+    /// Detaches `slot_id` from its parent and removes it along with all of its descendants.
+    /// The removed slots are returned in no particular order.
+    fn remove_subtree(&mut self, slot_id: SlotId) -> Vec<(SlotId, Slot)> {
+        if let Some(parent_id) = self.get(slot_id).parent {
+            let parent_slot = self.get_mut(parent_id);
+            parent_slot.children.retain(|&child_id| child_id != slot_id);
+        }
+        let mut removed = Vec::new();
+        let mut pending = vec![slot_id];
+        while let Some(slot_id) = pending.pop() {
+            let slot = self.arena.remove(slot_id.0).unwrap();
+            pending.extend(slot.children.iter().cloned());
+            removed.push((slot_id, slot));
+        }
+        self.dirty = true;
+        removed
     }
 }
 
@@ -326,55 +921,66 @@ pub enum CycleFocusDirection {
     Next,
 }
 
+/// An entry in `Gui::draw`'s traversal stack.
+enum DrawStep<D> {
+    Slot {
+        slot_id: SlotId,
+        parent_offset: (Scalar, Scalar),
+        draw_context: D,
+        clip: Option<Bounds>,
+    },
+    /// Restores the clip that was active before a clipped subtree.
+    RestoreClip(Option<Bounds>),
+}
+
 pub struct Gui<C: Context> {
     pub slots: Slots,
-    widgets: HashMap<WidgetId, Box<dyn Widget<C>>>,
+    widgets: Arena<Box<dyn Widget<C>>>,
     slot_style_overrides: HashMap<SlotId, HashMap<String, C::StyleFieldValue>>,
     root_slot_id: SlotId,
-    next_widget_id: WidgetId,
     focused_slot_id: Option<SlotId>,
     dirty: bool,
 }
 
 impl<C: Context> Gui<C> {
     pub fn new(root_bounds: Bounds) -> Gui<C> {
-        let root_slot_id = SlotId(0);
-        let mut slots = HashMap::new();
-        slots.insert(
-            root_slot_id,
-            Slot {
-                bounds: Bounds::zero(),
-                parent: None,
-                children: Vec::new(),
-                widget_id: None,
-                info: SlotInfo {
-                    hidden: false,
-                    minimum_size: root_bounds.size,
-                    expand_x: false,
-                    expand_y: false,
-                    grow_x: GrowDirection::End,
-                    grow_y: GrowDirection::End,
-                    anchor_left: 0.0,
-                    anchor_right: 1.0,
-                    anchor_top: 0.0,
-                    anchor_bottom: 1.0,
-                    margin_left: scalar::ZERO,
-                    margin_right: scalar::ZERO,
-                    margin_top: scalar::ZERO,
-                    margin_bottom: scalar::ZERO,
-                },
+        let mut slots = Arena::new();
+        let root_slot_id = SlotId(slots.insert(Slot {
+            bounds: Bounds::zero(),
+            parent: None,
+            children: Vec::new(),
+            widget_id: None,
+            name: None,
+            info: SlotInfo {
+                hidden: false,
+                clip_contents: false,
+                minimum_size: root_bounds.size,
+                maximum_size: None,
+                aspect_ratio: None,
+                expand_x: false,
+                expand_y: false,
+                stretch_ratio: 1.0,
+                layout_data: LayoutData::new(),
+                grow_x: GrowDirection::End,
+                grow_y: GrowDirection::End,
+                anchor_left: 0.0,
+                anchor_right: 1.0,
+                anchor_top: 0.0,
+                anchor_bottom: 1.0,
+                margin_left: scalar::ZERO,
+                margin_right: scalar::ZERO,
+                margin_top: scalar::ZERO,
+                margin_bottom: scalar::ZERO,
             },
-        );
+        }));
         Gui {
             slots: Slots {
-                map: slots,
-                next_slot_id: SlotId(1),
+                arena: slots,
                 dirty: true,
             },
             slot_style_overrides: HashMap::new(),
-            widgets: HashMap::new(),
+            widgets: Arena::new(),
             root_slot_id,
-            next_widget_id: WidgetId(0),
             focused_slot_id: None,
             dirty: true,
         }
@@ -388,17 +994,27 @@ impl<C: Context> Gui<C> {
     ) {
         self.slot_style_overrides
             .entry(slot_id)
-            .or_insert_with(HashMap::new)
+            .or_default()
             .insert(field_name.into(), value.into());
     }
 
+    fn takes_focus(&self, slot_id: SlotId) -> bool {
+        match self.slots.get(slot_id).widget_id {
+            Some(widget_id) => self.widgets.get(widget_id.0).unwrap().takes_focus(),
+            None => false,
+        }
+    }
+
+    /// Finds the first slot that takes focus in `slot_id`'s subtree, visiting each slot before its
+    /// children (or, if `reverse`, the last one in that order). Hidden subtrees are skipped.
     fn find_focusable_recursive(&self, slot_id: SlotId, reverse: bool) -> Option<SlotId> {
         let slot = self.slots.get(slot_id);
-        if let Some(widget_id) = slot.widget_id {
-            let widget = &self.widgets[&widget_id];
-            if widget.takes_focus() {
-                return Some(slot_id);
-            }
+        if slot.info.hidden {
+            return None;
+        }
+        let takes_focus = self.takes_focus(slot_id);
+        if takes_focus && !reverse {
+            return Some(slot_id);
         }
         // TODO: Probably can do this without allocating?
         let mut children = slot.children().to_vec();
@@ -410,11 +1026,30 @@ impl<C: Context> Gui<C> {
                 return Some(found);
             }
         }
-        None
+        if takes_focus {
+            Some(slot_id)
+        } else {
+            None
+        }
     }
 
+    /// Moves focus to the next or previous slot that takes focus, or to the first (or last) one if
+    /// nothing is focused.
+    ///
+    /// Slots are visited in tree order, each slot before its children, so focus moves from a
+    /// focusable container (such as a `tab_container::TabContainer`) into its contents before
+    /// moving on to the container's next sibling. Hidden slots and their descendants are skipped.
     pub fn cycle_focus(&mut self, direction: CycleFocusDirection) {
         if let Some(focused_slot_id) = self.focused_slot_id {
+            if direction == CycleFocusDirection::Next {
+                let children = self.slots.get(focused_slot_id).children().to_vec();
+                for child_id in children {
+                    if let Some(slot_id) = self.find_focusable_recursive(child_id, false) {
+                        self.focused_slot_id = Some(slot_id);
+                        return;
+                    }
+                }
+            }
             let mut current_slot_id = focused_slot_id;
             while let Some(parent_slot_id) = self.slots.get(current_slot_id).parent {
                 let parent_slot = self.slots.get(parent_slot_id);
@@ -443,12 +1078,19 @@ impl<C: Context> Gui<C> {
                             .collect()
                     }
                 };
+                let reverse = direction == CycleFocusDirection::Previous;
                 for candidate_slot_id in candidate_slot_ids.into_iter().rev() {
-                    if let Some(slot_id) = self.find_focusable_recursive(candidate_slot_id, false) {
+                    if let Some(slot_id) = self.find_focusable_recursive(candidate_slot_id, reverse)
+                    {
                         self.focused_slot_id = Some(slot_id);
                         return;
                     }
                 }
+                // A focusable parent comes before its children.
+                if reverse && self.takes_focus(parent_slot_id) {
+                    self.focused_slot_id = Some(parent_slot_id);
+                    return;
+                }
                 current_slot_id = parent_slot_id;
             }
         }
@@ -468,10 +1110,16 @@ impl<C: Context> Gui<C> {
     }
 
     pub fn add_widget<W: Widget<C>>(&mut self, widget: W) -> WidgetId {
-        let widget_id = self.next_widget_id;
-        self.next_widget_id.0 += 1;
-        self.widgets.insert(widget_id, Box::new(widget));
-        widget_id
+        self.add_boxed_widget(Box::new(widget))
+    }
+
+    fn add_boxed_widget(&mut self, widget: Box<dyn Widget<C>>) -> WidgetId {
+        WidgetId(self.widgets.insert(widget))
+    }
+
+    /// Starts building a tree of slots beneath `parent_id`.
+    pub fn build(&mut self, parent_id: SlotId) -> TreeBuilder<'_, C> {
+        TreeBuilder::new(self, parent_id)
     }
 
     pub fn add_slot_with_widget<W: Widget<C>>(
@@ -480,75 +1128,248 @@ impl<C: Context> Gui<C> {
         slot_info: SlotInfo,
         widget: W,
     ) -> (SlotId, WidgetHandle<C, W>) {
-        let slot_id = self.slots.add(parent_id, slot_info);
+        let index = self.slots.get(parent_id).children.len();
+        self.insert_slot_at(parent_id, index, slot_info, widget)
+    }
+
+    /// Removes a slot and all of its descendants, dropping any widgets bound to them.
+    ///
+    /// Panics if `slot_id` is the root slot.
+    pub fn remove_slot(&mut self, slot_id: SlotId) {
+        self.try_remove_slot(slot_id)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_remove_slot(&mut self, slot_id: SlotId) -> Result<(), GuiError> {
+        self.slots.try_get(slot_id)?;
+        if slot_id == self.root_slot_id {
+            return Err(GuiError::RootSlot);
+        }
+        for (removed_slot_id, slot) in self.slots.remove_subtree(slot_id) {
+            if let Some(widget_id) = slot.widget_id {
+                self.widgets.remove(widget_id.0).unwrap();
+            }
+            self.slot_style_overrides.remove(&removed_slot_id);
+            if self.focused_slot_id == Some(removed_slot_id) {
+                self.focused_slot_id = None;
+            }
+        }
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Like `add_slot_with_widget`, but inserts the new slot as the `index`th child of
+    /// `parent_id` instead of appending it.
+    pub fn insert_slot_at<W: Widget<C>>(
+        &mut self,
+        parent_id: SlotId,
+        index: usize,
+        slot_info: SlotInfo,
+        widget: W,
+    ) -> (SlotId, WidgetHandle<C, W>) {
+        self.try_insert_slot_at(parent_id, index, slot_info, widget)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_insert_slot_at<W: Widget<C>>(
+        &mut self,
+        parent_id: SlotId,
+        index: usize,
+        slot_info: SlotInfo,
+        widget: W,
+    ) -> Result<(SlotId, WidgetHandle<C, W>), GuiError> {
+        let slot_id = self.slots.try_insert_at(parent_id, index, slot_info)?;
         let widget_id = self.add_widget(widget);
         let slot = self.slots.get_mut(slot_id);
         slot.widget_id = Some(widget_id);
-        (slot_id, WidgetHandle(widget_id, PhantomData))
+        Ok((slot_id, WidgetHandle(widget_id, PhantomData)))
+    }
+
+    /// Moves a slot within the tree. See `Slots::move_slot`.
+    pub fn move_slot(&mut self, slot_id: SlotId, new_parent_id: SlotId, index: usize) {
+        self.try_move_slot(slot_id, new_parent_id, index)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_move_slot(
+        &mut self,
+        slot_id: SlotId,
+        new_parent_id: SlotId,
+        index: usize,
+    ) -> Result<(), GuiError> {
+        self.slots.try_move_slot(slot_id, new_parent_id, index)?;
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Draws a slot on top of its siblings. See `Slots::raise_to_top`.
+    pub fn raise_to_top(&mut self, slot_id: SlotId) {
+        self.try_raise_to_top(slot_id)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_raise_to_top(&mut self, slot_id: SlotId) -> Result<(), GuiError> {
+        self.slots.try_raise_to_top(slot_id)?;
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Draws a slot beneath its siblings. See `Slots::lower_to_bottom`.
+    pub fn lower_to_bottom(&mut self, slot_id: SlotId) {
+        self.try_lower_to_bottom(slot_id)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_lower_to_bottom(&mut self, slot_id: SlotId) -> Result<(), GuiError> {
+        self.slots.try_lower_to_bottom(slot_id)?;
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Swaps two children of `parent_id`. See `Slots::swap_children`.
+    pub fn swap_children(&mut self, parent_id: SlotId, a: usize, b: usize) {
+        self.try_swap_children(parent_id, a, b)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_swap_children(
+        &mut self,
+        parent_id: SlotId,
+        a: usize,
+        b: usize,
+    ) -> Result<(), GuiError> {
+        self.slots.try_swap_children(parent_id, a, b)?;
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Resolves a slot path relative to the root slot. See `Slots::find` for the path syntax.
+    pub fn find_slot(&self, path: &str) -> Result<SlotId, GuiError> {
+        self.slots.find(self.root_slot_id, path)
+    }
+
+    /// Resolves a slot path relative to `slot_id`. See `Slots::find` for the path syntax.
+    pub fn find_slot_from(&self, slot_id: SlotId, path: &str) -> Result<SlotId, GuiError> {
+        self.slots.find(slot_id, path)
+    }
+
+    /// Resolves a slot path relative to the root slot and returns a handle to its widget,
+    /// provided the widget is of type `W`.
+    pub fn find_widget<W: Widget<C>>(&self, path: &str) -> Result<WidgetHandle<C, W>, GuiError> {
+        let slot_id = self.find_slot(path)?;
+        self.widget_handle(slot_id)
+    }
+
+    /// Returns a handle to the widget bound to `slot_id`, provided it is of type `W`.
+    pub fn widget_handle<W: Widget<C>>(
+        &self,
+        slot_id: SlotId,
+    ) -> Result<WidgetHandle<C, W>, GuiError> {
+        let widget_id = self.slots
+            .try_get(slot_id)?
+            .widget_id
+            .ok_or(GuiError::NoWidget(slot_id))?;
+        let handle = WidgetHandle(widget_id, PhantomData);
+        self.try_get_widget(handle)?;
+        Ok(handle)
+    }
+
+    /// Returns `true` if `widget_id` refers to a widget that has not been removed.
+    pub fn contains_widget(&self, widget_id: WidgetId) -> bool {
+        self.widgets.contains(widget_id.0)
     }
 
     pub fn get_widget<W: Widget<C>>(&self, handle: WidgetHandle<C, W>) -> &W {
-        self.widgets
-            .get(&handle.0)
-            .and_then(|w| w.downcast_ref())
-            .unwrap()
+        self.try_get_widget(handle)
+            .unwrap_or_else(|err| panic!("{}", err))
     }
 
     pub fn get_widget_mut<W: Widget<C>>(&mut self, handle: WidgetHandle<C, W>) -> &mut W {
+        self.try_get_widget_mut(handle)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_get_widget<W: Widget<C>>(
+        &self,
+        handle: WidgetHandle<C, W>,
+    ) -> Result<&W, GuiError> {
+        let widget = self.widgets
+            .get((handle.0).0)
+            .map_err(|err| widget_lookup_error(handle.0, err))?;
+        widget.downcast_ref().ok_or_else(|| GuiError::WrongWidgetType {
+            expected: std::any::type_name::<W>(),
+            found_kind_id: widget.kind_id(),
+        })
+    }
+
+    pub fn try_get_widget_mut<W: Widget<C>>(
+        &mut self,
+        handle: WidgetHandle<C, W>,
+    ) -> Result<&mut W, GuiError> {
         // Very possible we will change something that could affect layout, so mark as dirty.
         self.dirty = true;
-        self.widgets
-            .get_mut(&handle.0)
-            .and_then(|w| w.downcast_mut())
-            .unwrap()
+        let widget = self.widgets
+            .get_mut((handle.0).0)
+            .map_err(|err| widget_lookup_error(handle.0, err))?;
+        if !widget.is::<W>() {
+            return Err(GuiError::WrongWidgetType {
+                expected: std::any::type_name::<W>(),
+                found_kind_id: widget.kind_id(),
+            });
+        }
+        Ok(widget.downcast_mut().unwrap())
     }
 
+    /// Also records which slots' minimum sizes depend on their available size, along with the
+    /// size that was used, in `size_dependent`, and which slots have such a slot in their subtree
+    /// in `size_dependent_subtrees`.
     fn calculate_minimum_sizes_recursive(
         &mut self,
         slot_id: SlotId,
         theme: &Theme<C::ThemeResources, C::StyleFieldValue>,
         cache: &mut HashMap<SlotId, Dimensions>,
+        size_dependent: &mut Vec<(SlotId, Dimensions)>,
+        size_dependent_subtrees: &mut HashSet<SlotId>,
     ) {
-        let (children, widget_id) = {
-            let slot = self.slots.get(slot_id);
-            (slot.children.clone(), slot.widget_id)
-        };
+        let children = self.slots.get(slot_id).children.clone();
         // Update children first, since parent minimum size may depend on children.
-        for child_id in children {
-            self.calculate_minimum_sizes_recursive(child_id, theme, cache);
-        }
-        let slot = self.slots.get(slot_id);
-        if let Some(widget_id) = widget_id {
-            let widget = self.widgets.get_mut(&widget_id).unwrap();
-            let style_overrides = self.slot_style_overrides.get(&slot_id);
-            let style = SlotStyle {
-                widget_kind_id: widget.kind_id(),
+        for &child_id in &children {
+            self.calculate_minimum_sizes_recursive(
+                child_id,
                 theme,
-                field_overrides: style_overrides,
-            };
-            let args = MinimumSizeArgs {
-                slots: &self.slots,
-                slot_id,
-                minimum_size_cache: cache,
-                resources: &theme.resources,
-                style: &style,
-            };
-            let widget_minimum_size = widget.minimum_size(args);
-            let slot = self.slots.get(slot_id);
-            let minimum_size = Dimensions::new(
-                slot.info.minimum_size.width.max(widget_minimum_size.width),
-                slot.info.minimum_size.height.max(widget_minimum_size.height),
+                cache,
+                size_dependent,
+                size_dependent_subtrees,
             );
-            cache.insert(slot_id, minimum_size);
-        } else {
-            cache.insert(slot_id, slot.info.minimum_size);
         }
+        let available_read = Cell::new(false);
+        let measurer = Measurer {
+            widgets: &self.widgets,
+            slot_style_overrides: &self.slot_style_overrides,
+            theme,
+            minimum_size_cache: cache,
+        };
+        let minimum_size =
+            measurer.size_with(&self.slots, slot_id, Some(&available_read), |widget, args| {
+                widget.minimum_size(args)
+            });
+        if available_read.get() {
+            size_dependent.push((slot_id, self.slots.get(slot_id).bounds.size));
+        }
+        if available_read.get()
+            || children.iter().any(|child_id| size_dependent_subtrees.contains(child_id))
+        {
+            size_dependent_subtrees.insert(slot_id);
+        }
+        cache.insert(slot_id, minimum_size);
     }
 
     fn layout_recursive(
         &mut self,
         slot_id: SlotId,
+        theme: &Theme<C::ThemeResources, C::StyleFieldValue>,
         minimum_size_cache: &HashMap<SlotId, Dimensions>,
+        size_dependent_subtrees: &HashSet<SlotId>,
     ) {
         let (bounds, children) = {
             let slot = self.slots.get(slot_id);
@@ -557,8 +1378,14 @@ impl<C: Context> Gui<C> {
 
         // TODO: Cleanup
         // Do basic anchor/margin calculations first
+        let measurer = Measurer {
+            widgets: &self.widgets,
+            slot_style_overrides: &self.slot_style_overrides,
+            theme,
+            minimum_size_cache,
+        };
         for child_id in &children {
-            let child = self.slots.get_mut(*child_id);
+            let child = self.slots.get(*child_id);
 
             // First we calculate the size of this child.
             let child_size = {
@@ -569,7 +1396,23 @@ impl<C: Context> Gui<C> {
                 Dimensions::new(right - left, bottom - top)
             };
 
-            let child_minimum_size = &minimum_size_cache[child_id];
+            // Now that the child's width is known, its height may depend on it, but only if some
+            // minimum size in its subtree depends on the available size.
+            let child_minimum_size = {
+                let minimum_size = minimum_size_cache[child_id];
+                if size_dependent_subtrees.contains(child_id) {
+                    let available = Dimensions::new(
+                        (child_size.width + child.info.margin_right - child.info.margin_left)
+                            .max(minimum_size.width),
+                        child_size.height + child.info.margin_bottom - child.info.margin_top,
+                    );
+                    let measured_size = measurer.measure(&self.slots, *child_id, available);
+                    Dimensions::new(minimum_size.width, measured_size.height)
+                } else {
+                    minimum_size
+                }
+            };
+            let child = self.slots.get_mut(*child_id);
             let (origin_top, origin_bottom) = match (
                 child_minimum_size.height > child_size.height,
                 child.info.grow_y,
@@ -630,24 +1473,41 @@ impl<C: Context> Gui<C> {
                     child_minimum_size.width);
             child.bounds.size.height = 
                 (origin_bottom - origin_top + child.info.margin_bottom - child.info.margin_top).max(
-                    child_minimum_size.height)
+                    child_minimum_size.height);
+
+            // A child capped by its maximum size or aspect ratio keeps the side it grows away
+            // from, just as a child grown past its anchors does.
+            let region_size = child.bounds.size;
+            child.bounds.size = child.info.constrain_size(region_size, child_minimum_size);
+            child.bounds.x += match child.info.grow_x {
+                GrowDirection::End => scalar::ZERO,
+                GrowDirection::Begin => region_size.width - child.bounds.size.width,
+                GrowDirection::Both => (region_size.width - child.bounds.size.width) / scalar::TWO,
+            };
+            child.bounds.y += match child.info.grow_y {
+                GrowDirection::End => scalar::ZERO,
+                GrowDirection::Begin => region_size.height - child.bounds.size.height,
+                GrowDirection::Both => {
+                    (region_size.height - child.bounds.size.height) / scalar::TWO
+                }
+            };
         }
 
         let slot = self.slots.get(slot_id);
         if let Some(widget_id) = slot.widget_id {
-            let widget = &self.widgets[&widget_id];
+            let widget = &self.widgets.get(widget_id.0).unwrap();
             if !slot.children.is_empty() {
                 let args = LayoutChildrenArgs {
                     slots: &mut self.slots,
                     slot_id,
                     minimum_size_cache,
-                    phantom: PhantomData,
+                    measurer: &measurer,
                 };
                 widget.layout_children(args);
             }
         }
         for child_id in &children {
-            self.layout_recursive(*child_id, minimum_size_cache);
+            self.layout_recursive(*child_id, theme, minimum_size_cache, size_dependent_subtrees);
         }
     }
 
@@ -656,14 +1516,36 @@ impl<C: Context> Gui<C> {
         &mut self,
         theme: &Theme<C::ThemeResources, C::StyleFieldValue>,
     ) -> bool {
+        // Minimum sizes that depend on the available size may change once layout gives slots new
+        // sizes, so repeat until those sizes settle. This normally takes at most two passes; the
+        // limit guards against sizes that never settle.
+        const MAX_PASSES: usize = 4;
+
         if self.dirty || self.slots.dirty {
-            let mut minimum_size_cache = HashMap::new();
-            self.calculate_minimum_sizes_recursive(
-                self.root_slot_id,
-                theme,
-                &mut minimum_size_cache,
-            );
-            self.layout_recursive(self.root_slot_id, &minimum_size_cache);
+            for _ in 0..MAX_PASSES {
+                let mut minimum_size_cache = HashMap::new();
+                let mut size_dependent = Vec::new();
+                let mut size_dependent_subtrees = HashSet::new();
+                self.calculate_minimum_sizes_recursive(
+                    self.root_slot_id,
+                    theme,
+                    &mut minimum_size_cache,
+                    &mut size_dependent,
+                    &mut size_dependent_subtrees,
+                );
+                self.layout_recursive(
+                    self.root_slot_id,
+                    theme,
+                    &minimum_size_cache,
+                    &size_dependent_subtrees,
+                );
+                let settled = size_dependent
+                    .iter()
+                    .all(|&(slot_id, size)| self.slots.get(slot_id).bounds.size == size);
+                if settled {
+                    break;
+                }
+            }
             self.dirty = false;
             self.slots.dirty = false;
             true
@@ -672,6 +1554,27 @@ impl<C: Context> Gui<C> {
         }
     }
 
+    /// The area, relative to `slot_id`, that its descendants are clipped to: its bounds if
+    /// `SlotInfo::clip_contents` is set, intersected with its widget's `Widget::clip_rect`.
+    fn clip_rect(&self, slot_id: SlotId) -> Option<Bounds> {
+        let slot = self.slots.get(slot_id);
+        let size = slot.bounds.size;
+        let widget_clip_rect = slot
+            .widget_id
+            .and_then(|widget_id| self.widgets.get(widget_id.0).unwrap().clip_rect(size));
+        let slot_clip_rect = if slot.info.clip_contents {
+            Some(Bounds::new(scalar::ZERO, scalar::ZERO, size.width, size.height))
+        } else {
+            None
+        };
+        match (slot_clip_rect, widget_clip_rect) {
+            (Some(slot_clip_rect), Some(widget_clip_rect)) => {
+                Some(slot_clip_rect.intersection(&widget_clip_rect))
+            }
+            (slot_clip_rect, widget_clip_rect) => slot_clip_rect.or(widget_clip_rect),
+        }
+    }
+
     // TODO: Cleanup. This event offset shenanigans is extremely obnoxious...
     fn process_event_recursive(
         &mut self,
@@ -679,25 +1582,40 @@ impl<C: Context> Gui<C> {
         theme: &Theme<C::ThemeResources, C::StyleFieldValue>,
         event: &mut C::InputEvent,
         signals: &mut Vec<(SlotId, Signal)>,
-    ) {
+    ) -> bool {
         let (bounds, children) = {
             let slot = self.slots.get(slot_id);
             if slot.info.hidden {
-                return;
+                return false;
             }
             (slot.bounds, slot.children.clone())
         };
 
         event.offset_coordinates(-bounds.x, -bounds.y);
-        for child_id in children.iter().rev() {
-            self.process_event_recursive(*child_id, theme, event, signals);
+        // Presses and scrolls outside a clipped area can't reach the descendants clipped by it.
+        // Moves and releases always can, so that a drag that started inside can leave the area.
+        let clip_rect = self.clip_rect(slot_id);
+        let pointer_clipped = match (clip_rect, event.pointer_event()) {
+            (Some(clip_rect), Some(PointerEvent::Pressed { x, y }))
+            | (Some(clip_rect), Some(PointerEvent::Scrolled { x, y, .. })) => {
+                !clip_rect.contains_point(x, y)
+            }
+            _ => false,
+        };
+        let handled = !pointer_clipped
+            && children
+                .iter()
+                .rev()
+                .any(|&child_id| self.process_event_recursive(child_id, theme, event, signals));
+        event.offset_coordinates(bounds.x, bounds.y);
+        if handled {
+            return true;
         }
 
-        event.offset_coordinates(bounds.x, bounds.y);
         let slot = self.slots.get(slot_id);
         let slot_bounds = slot.bounds;
         if let Some(widget_id) = slot.widget_id {
-            let widget = self.widgets.get_mut(&widget_id).unwrap();
+            let widget = self.widgets.get_mut(widget_id.0).unwrap();
             let focused = self.focused_slot_id == Some(slot_id);
             let bounds = Bounds::new(
                 scalar::ZERO,
@@ -729,7 +1647,9 @@ impl<C: Context> Gui<C> {
             if event_result.request_focus && widget.takes_focus() {
                 self.focused_slot_id = Some(slot_id);
             }
+            return event_result.handled;
         }
+        false
     }
 
     pub fn process_event(
@@ -751,14 +1671,28 @@ impl<C: Context> Gui<C> {
         draw_context: C::DrawContext,
     ) -> Vec<C::DrawCommand> {
         let mut draw_commands = Vec::new();
-        let mut slot_ids = vec![(self.root_slot_id, (scalar::ZERO, scalar::ZERO), draw_context)];
-        while let Some((slot_id, parent_offset, mut draw_context)) = slot_ids.pop() {
+        let mut steps = vec![DrawStep::Slot {
+            slot_id: self.root_slot_id,
+            parent_offset: (scalar::ZERO, scalar::ZERO),
+            draw_context,
+            clip: None,
+        }];
+        while let Some(step) = steps.pop() {
+            let (slot_id, parent_offset, mut draw_context, clip) = match step {
+                DrawStep::Slot { slot_id, parent_offset, draw_context, clip } => {
+                    (slot_id, parent_offset, draw_context, clip)
+                }
+                DrawStep::RestoreClip(clip) => {
+                    draw_commands.extend(C::clip_command(clip));
+                    continue;
+                }
+            };
             let slot = self.slots.get(slot_id);
             if slot.info.hidden {
                 continue;
             }
             if let Some(widget_id) = slot.widget_id {
-                let widget = &self.widgets[&widget_id];
+                let widget = &self.widgets.get(widget_id.0).unwrap();
                 let style_overrides = self.slot_style_overrides.get(&slot_id);
                 let style = SlotStyle {
                     widget_kind_id: widget.kind_id(),
@@ -774,6 +1708,7 @@ impl<C: Context> Gui<C> {
                 );
                 let args = DrawArgs {
                     bounds,
+                    clip,
                     focused,
                     resources: &theme.resources,
                     style: &style,
@@ -782,10 +1717,29 @@ impl<C: Context> Gui<C> {
                 };
                 widget.draw(args);
             }
+
+            let child_offset_x = parent_offset.0 + slot.bounds.x;
+            let child_offset_y = parent_offset.1 + slot.bounds.y;
+            let mut child_clip = clip;
+            if let Some(clip_rect) = self.clip_rect(slot_id) {
+                let clip_rect = Bounds {
+                    x: clip_rect.x + child_offset_x,
+                    y: clip_rect.y + child_offset_y,
+                    ..clip_rect
+                };
+                let clip_rect = clip.map_or(clip_rect, |clip| clip.intersection(&clip_rect));
+                draw_commands.extend(C::clip_command(Some(clip_rect)));
+                // Popped once every descendant has been drawn.
+                steps.push(DrawStep::RestoreClip(clip));
+                child_clip = Some(clip_rect);
+            }
             for child_id in slot.children.iter().rev() {
-                let child_offset_x = parent_offset.0 + slot.bounds.x;
-                let child_offset_y = parent_offset.1 + slot.bounds.y;
-                slot_ids.push((*child_id, (child_offset_x, child_offset_y), draw_context));
+                steps.push(DrawStep::Slot {
+                    slot_id: *child_id,
+                    parent_offset: (child_offset_x, child_offset_y),
+                    draw_context,
+                    clip: child_clip,
+                });
             }
         }
         draw_commands
@@ -0,0 +1,155 @@
+//! Constructing widgets by kind id, for scenes and other data-driven tooling.
+
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+use std::str::FromStr;
+
+use crate::{Context, Gui, GuiError, SlotId, SlotInfo, Widget};
+
+/// A bag of textual widget properties, keyed by property name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Properties {
+    values: BTreeMap<String, String>,
+}
+
+impl Properties {
+    pub fn new() -> Properties {
+        Properties::default()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    pub fn set<K: Into<String>, V: ToString>(&mut self, key: K, value: V) {
+        self.values.insert(key.into(), value.to_string());
+    }
+
+    /// Like `set`, but consumes and returns `self` for chaining.
+    pub fn with<K: Into<String>, V: ToString>(mut self, key: K, value: V) -> Properties {
+        self.set(key, value);
+        self
+    }
+
+    /// Parses a required property.
+    pub fn parse<T: FromStr>(&self, key: &str) -> Result<T, GuiError> {
+        let value = self.get(key).ok_or_else(|| GuiError::MissingProperty {
+            key: key.to_string(),
+        })?;
+        value.parse().map_err(|_| GuiError::InvalidProperty {
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+    }
+
+    /// Parses an optional property, returning `default` if it isn't set.
+    pub fn parse_or<T: FromStr>(&self, key: &str, default: T) -> Result<T, GuiError> {
+        match self.get(key) {
+            Some(_) => self.parse(key),
+            None => Ok(default),
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.values.remove(key)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Iterates over all properties, ordered by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values.iter().map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+}
+
+pub(super) type Constructor<C> = dyn Fn(&Properties) -> Result<Box<dyn Widget<C>>, GuiError>;
+
+/// Maps widget kind ids (as returned by `Widget::kind_id`) to constructors.
+pub struct WidgetRegistry<C: Context> {
+    constructors: HashMap<String, Rc<Constructor<C>>>,
+}
+
+impl<C: Context> WidgetRegistry<C> {
+    pub fn new() -> WidgetRegistry<C> {
+        WidgetRegistry {
+            constructors: HashMap::new(),
+        }
+    }
+
+    /// Registers the constructor for widgets of kind `kind_id`, replacing any previous one.
+    pub fn register<W, F>(&mut self, kind_id: &str, constructor: F)
+    where
+        W: Widget<C>,
+        F: Fn(&Properties) -> Result<W, GuiError> + 'static,
+    {
+        self.constructors.insert(
+            kind_id.to_string(),
+            Rc::new(move |properties: &Properties| {
+                constructor(properties).map(|widget| Box::new(widget) as Box<dyn Widget<C>>)
+            }),
+        );
+    }
+
+    pub fn unregister(&mut self, kind_id: &str) -> bool {
+        self.constructors.remove(kind_id).is_some()
+    }
+
+    pub fn contains(&self, kind_id: &str) -> bool {
+        self.constructors.contains_key(kind_id)
+    }
+
+    /// Iterates over all registered kind ids, in no particular order.
+    pub fn kinds(&self) -> impl Iterator<Item = &str> {
+        self.constructors.keys().map(String::as_str)
+    }
+
+    pub fn create(
+        &self,
+        kind_id: &str,
+        properties: &Properties,
+    ) -> Result<Box<dyn Widget<C>>, GuiError> {
+        self.constructor(kind_id)?(properties)
+    }
+
+    pub(super) fn constructor(&self, kind_id: &str) -> Result<Rc<Constructor<C>>, GuiError> {
+        self.constructors
+            .get(kind_id)
+            .cloned()
+            .ok_or_else(|| GuiError::UnknownWidgetKind(kind_id.to_string()))
+    }
+
+    /// Constructs a new widget of the same kind as `widget`, from the properties it writes with
+    /// `Widget::write_properties`.
+    pub fn recreate(&self, widget: &dyn Widget<C>) -> Result<Box<dyn Widget<C>>, GuiError> {
+        let mut properties = Properties::new();
+        widget.write_properties(&mut properties);
+        self.create(widget.kind_id(), &properties)
+    }
+}
+
+impl<C: Context> Default for WidgetRegistry<C> {
+    fn default() -> WidgetRegistry<C> {
+        WidgetRegistry::new()
+    }
+}
+
+impl<C: Context> Gui<C> {
+    /// Adds a slot beneath `parent_id` bound to a widget constructed by `registry`.
+    pub fn add_slot_with_kind(
+        &mut self,
+        registry: &WidgetRegistry<C>,
+        parent_id: SlotId,
+        slot_info: SlotInfo,
+        kind_id: &str,
+        properties: &Properties,
+    ) -> Result<SlotId, GuiError> {
+        let widget = registry.create(kind_id, properties)?;
+        let index = self.slots.try_get(parent_id)?.children().len();
+        let slot_id = self.slots.try_insert_at(parent_id, index, slot_info)?;
+        let widget_id = self.add_boxed_widget(widget);
+        self.slots.get_mut(slot_id).widget_id = Some(widget_id);
+        Ok(slot_id)
+    }
+}
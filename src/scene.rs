@@ -0,0 +1,769 @@
+//! A small text format describing slot trees, loosely modelled on Godot's `.tscn` files.
+//!
+//! ```text
+//! # Comments start with `#`.
+//! [slot name="panel" kind="Panel"]
+//! minimum_size = 400 300
+//! widget.padding = 5
+//!
+//! [slot name="ok" parent="." kind="Button"]
+//! expand_x = true
+//! style.color = "#ff00ff"
+//!
+//! [slot parent="ok"]
+//! hidden = true
+//! ```
+//!
+//! The first section describes the scene's root slot. Every other section names its parent with a
+//! path relative to the root slot (`.`). Unnamed slots are referred to by their position among
+//! their parent's children in the file, e.g. `ok/#0`.
+//!
+//! Keys prefixed with `widget.` are passed to the widget's constructor as `Properties`, keys
+//! prefixed with `style.` become style overrides, and all other keys set `SlotInfo` fields. Values
+//! may be wrapped in double quotes to preserve surrounding whitespace.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::clone::clone_widget;
+use crate::default_layout::{CrossAxisAlignment, ItemSettings};
+use crate::flex_layout::FlexItem;
+use crate::{
+    is_valid_name, Context, Dimensions, GrowDirection, Gui, GuiError, Properties, Scalar,
+    SlotId, SlotInfo, Widget, WidgetRegistry,
+};
+
+/// A parsed scene description that can be instantiated into a `Gui`, or written back out as text
+/// with `to_string`.
+#[derive(Debug, Clone)]
+pub struct Scene {
+    /// Nodes in pre-order; the first node is the scene's root.
+    nodes: Vec<SceneNode>,
+}
+
+#[derive(Debug, Clone)]
+struct SceneNode {
+    parent: Option<usize>,
+    name: Option<String>,
+    kind_id: Option<String>,
+    info: SlotInfo,
+    properties: Properties,
+    style: Vec<(String, String)>,
+}
+
+impl Scene {
+    pub fn parse(text: &str) -> Result<Scene, GuiError> {
+        let mut nodes: Vec<SceneNode> = Vec::new();
+        let mut paths: HashMap<String, usize> = HashMap::new();
+        let mut node_paths: Vec<String> = Vec::new();
+        let mut child_counts: Vec<usize> = Vec::new();
+        for (line_index, line) in text.lines().enumerate() {
+            let parse_error = |message: String| GuiError::SceneParse {
+                line: line_index + 1,
+                message,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') {
+                let attributes = parse_header(line).map_err(parse_error)?;
+                let mut name = None;
+                let mut parent_path = None;
+                let mut kind_id = None;
+                for (key, value) in attributes {
+                    match key.as_str() {
+                        "name" if !is_valid_name(&value) => {
+                            return Err(parse_error(format!("invalid slot name `{}`", value)))
+                        }
+                        "name" => name = Some(value),
+                        "parent" => parent_path = Some(value),
+                        "kind" => kind_id = Some(value),
+                        _ => return Err(parse_error(format!("unknown attribute `{}`", key))),
+                    }
+                }
+                let parent = match (parent_path, nodes.is_empty()) {
+                    (Some(parent_path), false) => match paths.get(&parent_path) {
+                        Some(&parent) => Some(parent),
+                        None => {
+                            return Err(parse_error(format!("unknown parent `{}`", parent_path)))
+                        }
+                    },
+                    (None, true) => None,
+                    (Some(_), true) => {
+                        return Err(parse_error("root slot cannot have a parent".to_string()))
+                    }
+                    (None, false) => {
+                        return Err(parse_error("only the root slot may omit `parent`".to_string()))
+                    }
+                };
+                let path = match parent {
+                    Some(parent) => {
+                        child_counts[parent] += 1;
+                        child_path(&node_paths[parent], name.as_deref(), child_counts[parent] - 1)
+                    }
+                    None => ".".to_string(),
+                };
+                if paths.insert(path.clone(), nodes.len()).is_some() {
+                    return Err(parse_error(format!("duplicate slot `{}`", path)));
+                }
+                node_paths.push(path);
+                child_counts.push(0);
+                nodes.push(SceneNode {
+                    parent,
+                    name,
+                    kind_id,
+                    info: SlotInfo::default(),
+                    properties: Properties::new(),
+                    style: Vec::new(),
+                });
+            } else {
+                let node = nodes
+                    .last_mut()
+                    .ok_or_else(|| parse_error("expected a `[slot]` section".to_string()))?;
+                let equals_index = line
+                    .find('=')
+                    .ok_or_else(|| parse_error("expected `key = value`".to_string()))?;
+                let key = line[..equals_index].trim();
+                let value = parse_value(line[equals_index + 1..].trim()).map_err(parse_error)?;
+                if let Some(property) = key.strip_prefix("widget.") {
+                    node.properties.set(property, value);
+                } else if let Some(field) = key.strip_prefix("style.") {
+                    node.style.push((field.to_string(), value));
+                } else {
+                    set_slot_info_field(&mut node.info, key, &value).map_err(parse_error)?;
+                }
+            }
+        }
+        if nodes.is_empty() {
+            return Err(GuiError::SceneParse {
+                line: 0,
+                message: "scene has no slots".to_string(),
+            });
+        }
+        Ok(Scene { nodes })
+    }
+}
+
+impl fmt::Display for Scene {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let default_fields = slot_info_fields(&SlotInfo::default());
+        let mut node_paths: Vec<String> = Vec::with_capacity(self.nodes.len());
+        let mut child_counts = vec![0; self.nodes.len()];
+        for (index, node) in self.nodes.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "[slot")?;
+            if let Some(ref name) = node.name {
+                write!(f, " name={}", quote(name))?;
+            }
+            let path = match node.parent {
+                Some(parent) => {
+                    write!(f, " parent={}", quote(&node_paths[parent]))?;
+                    child_counts[parent] += 1;
+                    child_path(&node_paths[parent], node.name.as_deref(), child_counts[parent] - 1)
+                }
+                None => ".".to_string(),
+            };
+            node_paths.push(path);
+            if let Some(ref kind_id) = node.kind_id {
+                write!(f, " kind={}", quote(kind_id))?;
+            }
+            writeln!(f, "]")?;
+            for ((key, value), (_, default_value)) in
+                slot_info_fields(&node.info).into_iter().zip(&default_fields)
+            {
+                if value != *default_value {
+                    writeln!(f, "{} = {}", key, value)?;
+                }
+            }
+            for (key, value) in node.properties.iter() {
+                writeln!(f, "widget.{} = {}", key, quote_if_needed(value))?;
+            }
+            for (key, value) in &node.style {
+                writeln!(f, "style.{} = {}", key, quote_if_needed(value))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<C: Context> Gui<C> {
+    /// Parses `text` as a `Scene` and instantiates it beneath `parent_id`, returning the id of the
+    /// scene's root slot.
+    pub fn load_scene(
+        &mut self,
+        registry: &WidgetRegistry<C>,
+        parent_id: SlotId,
+        text: &str,
+    ) -> Result<SlotId, GuiError>
+    where
+        C::StyleFieldValue: FromStr,
+    {
+        let scene = Scene::parse(text)?;
+        self.instantiate_scene(registry, parent_id, &scene)
+    }
+
+    /// Creates the slots and widgets described by `scene` beneath `parent_id`, returning the id of
+    /// the scene's root slot. Nothing is added if any part of the scene fails to instantiate.
+    pub fn instantiate_scene(
+        &mut self,
+        registry: &WidgetRegistry<C>,
+        parent_id: SlotId,
+        scene: &Scene,
+    ) -> Result<SlotId, GuiError>
+    where
+        C::StyleFieldValue: FromStr,
+    {
+        let mut slot_ids: Vec<SlotId> = Vec::with_capacity(scene.nodes.len());
+        for node in &scene.nodes {
+            let node_parent_id = node.parent.map_or(parent_id, |parent| slot_ids[parent]);
+            match self.instantiate_scene_node(registry, node_parent_id, node) {
+                Ok(slot_id) => slot_ids.push(slot_id),
+                Err(err) => {
+                    if let Some(&root_slot_id) = slot_ids.first() {
+                        self.remove_slot(root_slot_id);
+                    }
+                    return Err(err);
+                }
+            }
+        }
+        Ok(slot_ids[0])
+    }
+
+    fn instantiate_scene_node(
+        &mut self,
+        registry: &WidgetRegistry<C>,
+        parent_id: SlotId,
+        node: &SceneNode,
+    ) -> Result<SlotId, GuiError>
+    where
+        C::StyleFieldValue: FromStr,
+    {
+        let widget = match node.kind_id {
+            Some(ref kind_id) => Some(registry.create(kind_id, &node.properties)?),
+            None => None,
+        };
+        let style = parse_style(&node.style)?;
+
+        let index = self.slots.try_get(parent_id)?.children().len();
+        let slot_id = self.slots.try_insert_at(parent_id, index, node.info.clone())?;
+        if let Some(ref name) = node.name {
+            if let Err(err) = self.slots.try_set_name(slot_id, name.clone()) {
+                self.remove_slot(slot_id);
+                return Err(err);
+            }
+        }
+        if let Some(widget) = widget {
+            let widget_id = self.add_boxed_widget(widget);
+            self.slots.get_mut(slot_id).widget_id = Some(widget_id);
+        }
+        if !style.is_empty() {
+            self.slot_style_overrides.insert(slot_id, style);
+        }
+        Ok(slot_id)
+    }
+
+    /// Parses `text` as a `Scene` and applies it to the existing tree rooted at `slot_id`. See
+    /// `reload_from_scene`.
+    pub fn reload_scene(
+        &mut self,
+        registry: &WidgetRegistry<C>,
+        slot_id: SlotId,
+        text: &str,
+    ) -> Result<(), GuiError>
+    where
+        C::StyleFieldValue: FromStr,
+    {
+        let scene = Scene::parse(text)?;
+        self.reload_from_scene(registry, slot_id, &scene)
+    }
+
+    /// Updates the tree rooted at `slot_id` to match `scene`, for iterating on a scene while the
+    /// application is running.
+    ///
+    /// The scene's root corresponds to `slot_id` itself. Below it, scene slots are matched to live
+    /// children by name, and unnamed ones to unnamed children in order. Matched slots keep their
+    /// `SlotId`, focus and, if the widget kind is unchanged, their `WidgetId`. Changed widget
+    /// properties are passed to `Widget::apply_properties` on a copy of the widget (a clone if it
+    /// supports `Widget::as_clone_widget`, otherwise one recreated through `registry`), which then
+    /// takes the original's place. `SlotInfo`s and style overrides are replaced, unmatched live
+    /// slots are removed, new ones are instantiated, and children are reordered to follow the
+    /// scene. Layout is redone on the next `layout_if_needed`.
+    ///
+    /// The scene root's name, if it has one, is applied to `slot_id`.
+    ///
+    /// The tree is left untouched if the root's name is already used by a sibling of `slot_id`, or
+    /// if any widget or style value in the scene fails to load.
+    pub fn reload_from_scene(
+        &mut self,
+        registry: &WidgetRegistry<C>,
+        slot_id: SlotId,
+        scene: &Scene,
+    ) -> Result<(), GuiError>
+    where
+        C::StyleFieldValue: FromStr,
+    {
+        let parent_id = self.slots.try_get(slot_id)?.parent;
+        if let (Some(parent_id), Some(name)) = (parent_id, scene.nodes[0].name.as_ref()) {
+            match self.slots.find_child(parent_id, name) {
+                Some(sibling_id) if sibling_id != slot_id => {
+                    return Err(GuiError::DuplicateName {
+                        parent_id: Some(parent_id),
+                        name: name.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        // Match scene nodes to live slots. Parents precede their children in `scene.nodes`, so a
+        // node's match is always known by the time its own children are matched.
+        let mut live_slot_ids: Vec<Option<SlotId>> = vec![None; scene.nodes.len()];
+        live_slot_ids[0] = Some(slot_id);
+        let mut unmatched_slot_ids = Vec::new();
+        for index in 0..scene.nodes.len() {
+            if let Some(live_slot_id) = live_slot_ids[index] {
+                let mut candidates = self.slots.get(live_slot_id).children().to_vec();
+                let children = scene.nodes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, node)| node.parent == Some(index));
+                for (child_index, child) in children {
+                    let name = child.name.as_deref();
+                    let position = candidates
+                        .iter()
+                        .position(|&candidate_id| self.slots.get(candidate_id).name() == name);
+                    if let Some(position) = position {
+                        live_slot_ids[child_index] = Some(candidates.remove(position));
+                    }
+                }
+                unmatched_slot_ids.extend(candidates);
+            }
+        }
+
+        // Do everything that can fail before touching the tree.
+        let mut widget_changes = Vec::with_capacity(scene.nodes.len());
+        let mut styles = Vec::with_capacity(scene.nodes.len());
+        for (node, live_slot_id) in scene.nodes.iter().zip(&live_slot_ids) {
+            let live_widget = live_slot_id
+                .and_then(|live_slot_id| self.slots.get(live_slot_id).widget_id)
+                .map(|widget_id| &**self.widgets.get(widget_id.0).unwrap());
+            widget_changes.push(match (node.kind_id.as_ref(), live_widget) {
+                (Some(kind_id), Some(widget)) if widget.kind_id() == kind_id => {
+                    let mut properties = Properties::new();
+                    widget.write_properties(&mut properties);
+                    if properties == node.properties {
+                        WidgetChange::Keep
+                    } else {
+                        let mut updated = clone_widget(widget)
+                            .or_else(|_| registry.recreate(widget))?;
+                        updated.apply_properties(&node.properties)?;
+                        WidgetChange::Update(updated)
+                    }
+                }
+                (Some(kind_id), _) => {
+                    WidgetChange::Replace(registry.create(kind_id, &node.properties)?)
+                }
+                (None, _) => WidgetChange::Keep,
+            });
+            styles.push(parse_style::<C::StyleFieldValue>(&node.style)?);
+        }
+
+        for unmatched_slot_id in unmatched_slot_ids {
+            self.remove_slot(unmatched_slot_id);
+        }
+        let mut slot_ids: Vec<SlotId> = Vec::with_capacity(scene.nodes.len());
+        let mut child_counts = vec![0; scene.nodes.len()];
+        let nodes = scene.nodes.iter().zip(live_slot_ids).zip(widget_changes).zip(styles);
+        for (((node, live_slot_id), widget_change), style) in nodes {
+            let node_slot_id = match (node.parent, live_slot_id) {
+                (None, _) => {
+                    if let Some(ref name) = node.name {
+                        self.slots.try_set_name(slot_id, name.clone())?;
+                    }
+                    slot_id
+                }
+                (Some(parent), live_slot_id) => {
+                    let parent_slot_id = slot_ids[parent];
+                    let index = child_counts[parent];
+                    child_counts[parent] += 1;
+                    match live_slot_id {
+                        Some(live_slot_id) => {
+                            if self.slots.get(parent_slot_id).children()[index] != live_slot_id {
+                                self.slots.try_move_slot(live_slot_id, parent_slot_id, index)?;
+                            }
+                            live_slot_id
+                        }
+                        None => {
+                            let new_slot_id = self.slots.try_insert_at(
+                                parent_slot_id,
+                                index,
+                                SlotInfo::default(),
+                            )?;
+                            if let Some(ref name) = node.name {
+                                self.slots.try_set_name(new_slot_id, name.clone())?;
+                            }
+                            new_slot_id
+                        }
+                    }
+                }
+            };
+            slot_ids.push(node_slot_id);
+            self.slots.get_mut(node_slot_id).info = node.info.clone();
+
+            let old_widget_id = self.slots.get(node_slot_id).widget_id;
+            match (widget_change, old_widget_id) {
+                (WidgetChange::Replace(new_widget), _) => {
+                    if let Some(old_widget_id) = old_widget_id {
+                        self.widgets.remove(old_widget_id.0).unwrap();
+                    }
+                    let widget_id = self.add_boxed_widget(new_widget);
+                    self.slots.get_mut(node_slot_id).widget_id = Some(widget_id);
+                }
+                (WidgetChange::Update(updated), Some(old_widget_id)) => {
+                    *self.widgets.get_mut(old_widget_id.0).unwrap() = updated;
+                }
+                (WidgetChange::Keep, Some(old_widget_id)) if node.kind_id.is_none() => {
+                    self.widgets.remove(old_widget_id.0).unwrap();
+                    self.slots.get_mut(node_slot_id).widget_id = None;
+                }
+                _ => {}
+            }
+
+            if style.is_empty() {
+                self.slot_style_overrides.remove(&node_slot_id);
+            } else {
+                self.slot_style_overrides.insert(node_slot_id, style);
+            }
+        }
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Captures `slot_id` and its descendants as a `Scene`.
+    pub fn capture_scene(&self, slot_id: SlotId) -> Result<Scene, GuiError>
+    where
+        C::StyleFieldValue: fmt::Display,
+    {
+        self.slots.try_get(slot_id)?;
+        let mut indices = HashMap::new();
+        let mut nodes = Vec::new();
+        for node_slot_id in std::iter::once(slot_id).chain(self.slots.descendants(slot_id)) {
+            let slot = self.slots.get(node_slot_id);
+            let parent = if node_slot_id == slot_id {
+                None
+            } else {
+                slot.parent.map(|parent_id| indices[&parent_id])
+            };
+            let mut properties = Properties::new();
+            let kind_id = slot.widget_id.map(|widget_id| {
+                let widget = self.widgets.get(widget_id.0).unwrap();
+                widget.write_properties(&mut properties);
+                widget.kind_id().to_string()
+            });
+            let mut style: Vec<(String, String)> = self.slot_style_overrides
+                .get(&node_slot_id)
+                .map(|overrides| {
+                    overrides
+                        .iter()
+                        .map(|(field, value)| (field.clone(), value.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default();
+            style.sort();
+            indices.insert(node_slot_id, nodes.len());
+            nodes.push(SceneNode {
+                parent,
+                name: slot.name().map(str::to_string),
+                kind_id,
+                info: slot.info.clone(),
+                properties,
+                style,
+            });
+        }
+        Ok(Scene { nodes })
+    }
+
+    /// Writes `slot_id` and its descendants in the scene text format.
+    pub fn save_scene(&self, slot_id: SlotId) -> Result<String, GuiError>
+    where
+        C::StyleFieldValue: fmt::Display,
+    {
+        Ok(self.capture_scene(slot_id)?.to_string())
+    }
+}
+
+/// What reloading a scene does to a slot's widget, worked out before the tree is touched.
+enum WidgetChange<C: Context> {
+    /// Leaves the widget as it is, or removes it if the scene slot has no widget.
+    Keep,
+    /// Swaps in a widget with the scene's properties applied, under the same `WidgetId`.
+    Update(Box<dyn Widget<C>>),
+    /// Replaces the widget (if any) with a new one of a different kind.
+    Replace(Box<dyn Widget<C>>),
+}
+
+/// The path used to refer to a node in `parent` attributes, given its parent's path.
+fn child_path(parent_path: &str, name: Option<&str>, index_in_parent: usize) -> String {
+    let segment = match name {
+        Some(name) => name.to_string(),
+        None => format!("#{}", index_in_parent),
+    };
+    if parent_path == "." {
+        segment
+    } else {
+        format!("{}/{}", parent_path, segment)
+    }
+}
+
+/// All `SlotInfo` fields as scene keys and values, in the order they are written.
+fn slot_info_fields(info: &SlotInfo) -> Vec<(&'static str, String)> {
+    let item_settings = info.layout_data.get::<ItemSettings>().copied().unwrap_or_default();
+    let flex = info.layout_data.get::<FlexItem>().copied().unwrap_or_default();
+    vec![
+        ("hidden", info.hidden.to_string()),
+        ("clip_contents", info.clip_contents.to_string()),
+        (
+            "minimum_size",
+            format!("{} {}", info.minimum_size.width, info.minimum_size.height),
+        ),
+        (
+            "maximum_size",
+            info.maximum_size.map_or("none".to_string(), |size| {
+                format!("{} {}", size.width, size.height)
+            }),
+        ),
+        (
+            "aspect_ratio",
+            info.aspect_ratio.map_or("none".to_string(), |ratio| ratio.to_string()),
+        ),
+        ("expand_x", info.expand_x.to_string()),
+        ("expand_y", info.expand_y.to_string()),
+        ("stretch_ratio", info.stretch_ratio.to_string()),
+        (
+            "cross_axis_alignment",
+            cross_axis_alignment_name(item_settings.cross_axis_alignment).to_string(),
+        ),
+        ("flex_grow", flex.grow.to_string()),
+        ("flex_shrink", flex.shrink.to_string()),
+        (
+            "flex_basis",
+            flex.basis.map_or("auto".to_string(), |basis| basis.to_string()),
+        ),
+        ("grow_x", grow_direction_name(info.grow_x).to_string()),
+        ("grow_y", grow_direction_name(info.grow_y).to_string()),
+        ("anchor_left", info.anchor_left.to_string()),
+        ("anchor_right", info.anchor_right.to_string()),
+        ("anchor_top", info.anchor_top.to_string()),
+        ("anchor_bottom", info.anchor_bottom.to_string()),
+        ("margin_left", info.margin_left.to_string()),
+        ("margin_right", info.margin_right.to_string()),
+        ("margin_top", info.margin_top.to_string()),
+        ("margin_bottom", info.margin_bottom.to_string()),
+    ]
+}
+
+fn set_slot_info_field(info: &mut SlotInfo, key: &str, value: &str) -> Result<(), String> {
+    match key {
+        "hidden" => info.hidden = parse_field(key, value)?,
+        "clip_contents" => info.clip_contents = parse_field(key, value)?,
+        "minimum_size" => info.minimum_size = parse_dimensions(key, value)?,
+        "maximum_size" => {
+            info.maximum_size = match value {
+                "none" => None,
+                _ => Some(parse_dimensions(key, value)?),
+            }
+        }
+        "aspect_ratio" => {
+            info.aspect_ratio = match value {
+                "none" => None,
+                _ => Some(parse_field(key, value)?),
+            }
+        }
+        "expand_x" => info.expand_x = parse_field(key, value)?,
+        "expand_y" => info.expand_y = parse_field(key, value)?,
+        "stretch_ratio" => info.stretch_ratio = parse_field(key, value)?,
+        "cross_axis_alignment" => {
+            info.layout_data.get_or_insert_default::<ItemSettings>().cross_axis_alignment =
+                parse_cross_axis_alignment(key, value)?
+        }
+        "flex_grow" => {
+            info.layout_data.get_or_insert_default::<FlexItem>().grow = parse_field(key, value)?
+        }
+        "flex_shrink" => {
+            info.layout_data.get_or_insert_default::<FlexItem>().shrink = parse_field(key, value)?
+        }
+        "flex_basis" => {
+            info.layout_data.get_or_insert_default::<FlexItem>().basis = match value {
+                "auto" => None,
+                _ => Some(parse_field(key, value)?),
+            }
+        }
+        "grow_x" => info.grow_x = parse_grow_direction(key, value)?,
+        "grow_y" => info.grow_y = parse_grow_direction(key, value)?,
+        "anchor_left" => info.anchor_left = parse_field(key, value)?,
+        "anchor_right" => info.anchor_right = parse_field(key, value)?,
+        "anchor_top" => info.anchor_top = parse_field(key, value)?,
+        "anchor_bottom" => info.anchor_bottom = parse_field(key, value)?,
+        "margin_left" => info.margin_left = parse_field(key, value)?,
+        "margin_right" => info.margin_right = parse_field(key, value)?,
+        "margin_top" => info.margin_top = parse_field(key, value)?,
+        "margin_bottom" => info.margin_bottom = parse_field(key, value)?,
+        _ => return Err(format!("unknown slot field `{}`", key)),
+    }
+    Ok(())
+}
+
+fn parse_field<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for `{}`: `{}`", key, value))
+}
+
+fn parse_dimensions(key: &str, value: &str) -> Result<Dimensions, String> {
+    let mut parts = value.split_whitespace();
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(width), Some(height), None) => Ok(Dimensions::new(
+            parse_field::<Scalar>(key, width)?,
+            parse_field::<Scalar>(key, height)?,
+        )),
+        _ => Err(format!("expected `width height` for `{}`: `{}`", key, value)),
+    }
+}
+
+fn grow_direction_name(direction: GrowDirection) -> &'static str {
+    match direction {
+        GrowDirection::Begin => "begin",
+        GrowDirection::End => "end",
+        GrowDirection::Both => "both",
+    }
+}
+
+fn parse_grow_direction(key: &str, value: &str) -> Result<GrowDirection, String> {
+    match value {
+        "begin" => Ok(GrowDirection::Begin),
+        "end" => Ok(GrowDirection::End),
+        "both" => Ok(GrowDirection::Both),
+        _ => Err(format!("invalid value for `{}`: `{}`", key, value)),
+    }
+}
+
+fn cross_axis_alignment_name(alignment: Option<CrossAxisAlignment>) -> &'static str {
+    match alignment {
+        None => "default",
+        Some(CrossAxisAlignment::Start) => "start",
+        Some(CrossAxisAlignment::Center) => "center",
+        Some(CrossAxisAlignment::End) => "end",
+        Some(CrossAxisAlignment::Stretch) => "stretch",
+    }
+}
+
+fn parse_cross_axis_alignment(
+    key: &str,
+    value: &str,
+) -> Result<Option<CrossAxisAlignment>, String> {
+    match value {
+        "default" => Ok(None),
+        "start" => Ok(Some(CrossAxisAlignment::Start)),
+        "center" => Ok(Some(CrossAxisAlignment::Center)),
+        "end" => Ok(Some(CrossAxisAlignment::End)),
+        "stretch" => Ok(Some(CrossAxisAlignment::Stretch)),
+        _ => Err(format!("invalid value for `{}`: `{}`", key, value)),
+    }
+}
+
+/// Parses a `[slot key="value" ...]` section header into its attributes.
+fn parse_header(line: &str) -> Result<Vec<(String, String)>, String> {
+    if !line.ends_with(']') {
+        return Err("expected `]` at end of section header".to_string());
+    }
+    let inner = line[1..line.len() - 1].trim();
+    if !inner.starts_with("slot") {
+        return Err("expected `[slot ...]` section header".to_string());
+    }
+    let mut rest = inner["slot".len()..].trim_start();
+    let mut attributes = Vec::new();
+    while !rest.is_empty() {
+        let equals_index = rest
+            .find('=')
+            .ok_or_else(|| "expected `key=\"value\"` attribute".to_string())?;
+        let key = rest[..equals_index].trim().to_string();
+        let (value, remainder) = parse_quoted(rest[equals_index + 1..].trim_start())?;
+        attributes.push((key, value));
+        rest = remainder.trim_start();
+    }
+    Ok(attributes)
+}
+
+fn parse_style<S: FromStr>(style: &[(String, String)]) -> Result<HashMap<String, S>, GuiError> {
+    style
+        .iter()
+        .map(|(field, value)| {
+            let parsed_value = value.parse().map_err(|_| GuiError::InvalidStyleValue {
+                field: field.clone(),
+                value: value.clone(),
+            })?;
+            Ok((field.clone(), parsed_value))
+        })
+        .collect()
+}
+
+/// Parses a key's value, unquoting it if it is wrapped in double quotes.
+pub(super) fn parse_value(text: &str) -> Result<String, String> {
+    if !text.starts_with('"') {
+        return Ok(text.to_string());
+    }
+    let (value, remainder) = parse_quoted(text)?;
+    if !remainder.trim().is_empty() {
+        return Err("unexpected text after quoted value".to_string());
+    }
+    Ok(value)
+}
+
+/// Parses a double-quoted string at the start of `text`, returning it along with the remaining
+/// text.
+fn parse_quoted(text: &str) -> Result<(String, &str), String> {
+    if !text.starts_with('"') {
+        return Err("expected `\"`".to_string());
+    }
+    let mut value = String::new();
+    let mut chars = text.char_indices().skip(1);
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Ok((value, &text[index + 1..])),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => value.push('\n'),
+                Some((_, c @ '"')) | Some((_, c @ '\\')) => value.push(c),
+                _ => return Err("invalid escape sequence".to_string()),
+            },
+            c => value.push(c),
+        }
+    }
+    Err("unterminated string".to_string())
+}
+
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn quote_if_needed(value: &str) -> String {
+    if value.is_empty() || value.trim() != value || value.starts_with('"') || value.contains('\n') {
+        quote(value)
+    } else {
+        value.to_string()
+    }
+}
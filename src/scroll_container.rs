@@ -0,0 +1,480 @@
+//! A container that shows part of content larger than itself, scrolled with the mouse wheel or by
+//! dragging its scrollbars.
+//!
+//! Each visible child is laid out at its minimum size (but at least filling the container) and
+//! moved by the scroll offset, so drawing and hit-testing follow the scroll position without any
+//! help from the children. Children are clipped to the area inside the scrollbars.
+
+use std::cell::Cell;
+
+use crate::{
+    scalar, Bounds, Context, Dimensions, DrawArgs, Gui, GuiError, InputEvent, LayoutChildrenArgs,
+    MinimumSizeArgs, PointerEvent, ProcessEventArgs, ProcessEventResult, Scalar, SlotId, Slots,
+    Widget,
+};
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// Whether the content can be wider than the container. If not, it's given the container's
+    /// width.
+    pub horizontal: bool,
+    /// Whether the content can be taller than the container. If not, it's given the container's
+    /// height.
+    pub vertical: bool,
+    /// The space set aside along the right and bottom edges for the scrollbars of the axes that
+    /// scroll.
+    pub scrollbar_thickness: Scalar,
+    /// How far one unit of `PointerEvent::Scrolled` delta scrolls.
+    pub wheel_step: Scalar,
+}
+
+/// The track and thumb of one scrollbar.
+#[derive(Debug, Clone, Copy)]
+pub struct Scrollbar {
+    pub track: Bounds,
+    pub thumb: Bounds,
+}
+
+/// The scrollbars of the axes that scroll and currently overflow.
+#[derive(Debug, Clone, Copy)]
+pub struct Scrollbars {
+    pub horizontal: Option<Scrollbar>,
+    pub vertical: Option<Scrollbar>,
+}
+
+/// Draws a scroll container's scrollbars, which are positioned relative to `DrawArgs::bounds`.
+pub type Painter<C> = fn(DrawArgs<'_, C>, &Scrollbars);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+/// A scrollbar thumb being dragged, and where along the thumb it was grabbed.
+#[derive(Debug, Clone, Copy)]
+struct Drag {
+    axis: Axis,
+    grab_offset: Scalar,
+}
+
+pub struct ScrollContainer<C: Context> {
+    settings: Settings,
+    painter: Option<Painter<C>>,
+    offset: (Scalar, Scalar),
+    /// The content size as of the last layout or scroll, for drawing the scrollbars.
+    content_size: Cell<Dimensions>,
+    drag: Option<Drag>,
+}
+
+impl<C: Context> ScrollContainer<C> {
+    pub fn new(settings: Settings) -> ScrollContainer<C> {
+        ScrollContainer {
+            settings,
+            painter: None,
+            offset: (scalar::ZERO, scalar::ZERO),
+            content_size: Cell::new(Dimensions::zero()),
+            drag: None,
+        }
+    }
+
+    /// Draws the scrollbars with `painter`. Without one, the scrollbars still work but aren't
+    /// drawn.
+    pub fn with_painter(mut self, painter: Painter<C>) -> ScrollContainer<C> {
+        self.painter = Some(painter);
+        self
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// The scroll offset as of the last scroll or layout. This is the position in the content
+    /// shown at the top left corner of the viewport.
+    pub fn offset(&self) -> (Scalar, Scalar) {
+        self.offset
+    }
+
+    /// The area inside the scrollbars, relative to the container, given the container's `size`.
+    pub fn viewport(&self, size: Dimensions) -> Bounds {
+        let Settings { horizontal, vertical, scrollbar_thickness, .. } = self.settings;
+        let gutter = |scrolls: bool| if scrolls { scrollbar_thickness } else { scalar::ZERO };
+        Bounds::new(
+            scalar::ZERO,
+            scalar::ZERO,
+            (size.width - gutter(vertical)).max(scalar::ZERO),
+            (size.height - gutter(horizontal)).max(scalar::ZERO),
+        )
+    }
+
+    /// `offset` limited to the scrollable range for `content_size` in `viewport`.
+    fn clamp_offset(
+        &self,
+        offset: (Scalar, Scalar),
+        viewport: Bounds,
+        content_size: Dimensions,
+    ) -> (Scalar, Scalar) {
+        let clamp = |offset: Scalar, scrolls: bool, max: Scalar| {
+            if scrolls {
+                offset.min(max).max(scalar::ZERO)
+            } else {
+                scalar::ZERO
+            }
+        };
+        (
+            clamp(
+                offset.0,
+                self.settings.horizontal,
+                content_size.width - viewport.size.width,
+            ),
+            clamp(
+                offset.1,
+                self.settings.vertical,
+                content_size.height - viewport.size.height,
+            ),
+        )
+    }
+
+    /// Scrolls to `offset` (as far as the content allows), moving the children of `slot_id`
+    /// without waiting for the next layout. Returns whether the offset changed.
+    fn set_offset(
+        &mut self,
+        slots: &mut Slots,
+        slot_id: SlotId,
+        offset: (Scalar, Scalar),
+    ) -> bool {
+        let viewport = self.viewport(slots.get(slot_id).bounds.size);
+        let content_size = content_size(slots, slot_id);
+        self.content_size.set(content_size);
+        let old_offset = self.offset;
+        self.offset = self.clamp_offset(offset, viewport, content_size);
+        for child_id in crate::visible_children(slots, slot_id).collect::<Vec<_>>() {
+            let child = slots.get_mut(child_id);
+            child.bounds.x = viewport.x - self.offset.0;
+            child.bounds.y = viewport.y - self.offset.1;
+        }
+        self.offset != old_offset
+    }
+
+    /// The scrollbars for a container at `bounds` showing `content_size`.
+    pub fn scrollbars(&self, bounds: Bounds, content_size: Dimensions) -> Scrollbars {
+        let thickness = self.settings.scrollbar_thickness;
+        let viewport = self.viewport(bounds.size);
+        let offset = self.clamp_offset(self.offset, viewport, content_size);
+        let scrollbar = |track: Bounds, axis: Axis| {
+            let (track_length, viewport_length, content_length, offset) = match axis {
+                Axis::Horizontal => {
+                    (track.size.width, viewport.size.width, content_size.width, offset.0)
+                }
+                Axis::Vertical => {
+                    (track.size.height, viewport.size.height, content_size.height, offset.1)
+                }
+            };
+            let thumb_length = ((track_length as f32 * viewport_length as f32
+                / content_length as f32) as Scalar)
+                .max(thickness)
+                .min(track_length);
+            let thumb_offset = ((track_length - thumb_length) as f32 * offset as f32
+                / (content_length - viewport_length) as f32) as Scalar;
+            let thumb = match axis {
+                Axis::Horizontal => Bounds::new(
+                    track.x + thumb_offset,
+                    track.y,
+                    thumb_length,
+                    track.size.height,
+                ),
+                Axis::Vertical => Bounds::new(
+                    track.x,
+                    track.y + thumb_offset,
+                    track.size.width,
+                    thumb_length,
+                ),
+            };
+            Scrollbar { track, thumb }
+        };
+        let horizontal = if self.settings.horizontal && content_size.width > viewport.size.width {
+            let track = Bounds::new(
+                bounds.x + viewport.x,
+                bounds.y + viewport.y + viewport.size.height,
+                viewport.size.width,
+                thickness,
+            );
+            Some(scrollbar(track, Axis::Horizontal))
+        } else {
+            None
+        };
+        let vertical = if self.settings.vertical && content_size.height > viewport.size.height {
+            let track = Bounds::new(
+                bounds.x + viewport.x + viewport.size.width,
+                bounds.y + viewport.y,
+                thickness,
+                viewport.size.height,
+            );
+            Some(scrollbar(track, Axis::Vertical))
+        } else {
+            None
+        };
+        Scrollbars { horizontal, vertical }
+    }
+
+    /// The scroll offset that puts the start of the thumb at `position` along `axis`.
+    fn offset_for_thumb(
+        &self,
+        scrollbar: &Scrollbar,
+        axis: Axis,
+        position: Scalar,
+        viewport: Bounds,
+        content_size: Dimensions,
+    ) -> (Scalar, Scalar) {
+        let (track_start, track_length, thumb_length, scroll_length) = match axis {
+            Axis::Horizontal => (
+                scrollbar.track.x,
+                scrollbar.track.size.width,
+                scrollbar.thumb.size.width,
+                content_size.width - viewport.size.width,
+            ),
+            Axis::Vertical => (
+                scrollbar.track.y,
+                scrollbar.track.size.height,
+                scrollbar.thumb.size.height,
+                content_size.height - viewport.size.height,
+            ),
+        };
+        let free_length = track_length - thumb_length;
+        let offset = if free_length > scalar::ZERO {
+            ((position - track_start) as f32 * scroll_length as f32 / free_length as f32) as Scalar
+        } else {
+            scalar::ZERO
+        };
+        match axis {
+            Axis::Horizontal => (offset, self.offset.1),
+            Axis::Vertical => (self.offset.0, offset),
+        }
+    }
+}
+
+/// The size of the scrolled content: the largest of the visible children's sizes.
+fn content_size(slots: &Slots, slot_id: SlotId) -> Dimensions {
+    crate::visible_children(slots, slot_id).fold(Dimensions::zero(), |size, child_id| {
+        let child_size = slots.get(child_id).bounds.size;
+        Dimensions::new(
+            size.width.max(child_size.width),
+            size.height.max(child_size.height),
+        )
+    })
+}
+
+impl<C: Context> Widget<C> for ScrollContainer<C> {
+    fn kind_id(&self) -> &'static str {
+        "ScrollContainer"
+    }
+
+    fn takes_focus(&self) -> bool {
+        false
+    }
+
+    /// Along the axes that scroll, the container only needs room for its scrollbar; along the
+    /// others, it needs room for its largest child.
+    fn minimum_size(&self, args: MinimumSizeArgs<'_, C>) -> Dimensions {
+        let Settings { horizontal, vertical, scrollbar_thickness, .. } = self.settings;
+        let content_size = crate::visible_children(args.slots, args.slot_id)
+            .map(|child_id| args.minimum_size_cache[&child_id])
+            .fold(Dimensions::zero(), |size, child_size| {
+                Dimensions::new(
+                    size.width.max(child_size.width),
+                    size.height.max(child_size.height),
+                )
+            });
+        let gutter = |scrolls: bool| if scrolls { scrollbar_thickness } else { scalar::ZERO };
+        let width = if horizontal { scrollbar_thickness } else { content_size.width };
+        let height = if vertical { scrollbar_thickness } else { content_size.height };
+        Dimensions::new(width + gutter(vertical), height + gutter(horizontal))
+    }
+
+    fn layout_children(&self, args: LayoutChildrenArgs<'_, C>) {
+        let Settings { horizontal, vertical, .. } = self.settings;
+        let viewport = self.viewport(args.slots.get(args.slot_id).bounds.size);
+
+        let children = crate::visible_children(args.slots, args.slot_id).collect::<Vec<_>>();
+        let mut content_size = Dimensions::zero();
+        for &child_id in &children {
+            let child_minimum_size = args.minimum_size_cache[&child_id];
+            let width = if horizontal {
+                child_minimum_size.width.max(viewport.size.width)
+            } else {
+                viewport.size.width.max(child_minimum_size.width)
+            };
+            // The height may depend on the width the child was just given.
+            let height = if vertical {
+                args.measure(child_id, Dimensions::new(width, viewport.size.height))
+                    .height
+                    .max(viewport.size.height)
+            } else {
+                viewport.size.height.max(child_minimum_size.height)
+            };
+            args.slots.get_mut(child_id).bounds.size = Dimensions::new(width, height);
+            content_size = Dimensions::new(
+                content_size.width.max(width),
+                content_size.height.max(height),
+            );
+        }
+
+        self.content_size.set(content_size);
+        let offset = self.clamp_offset(self.offset, viewport, content_size);
+        for &child_id in &children {
+            let child = args.slots.get_mut(child_id);
+            child.bounds.x = viewport.x - offset.0;
+            child.bounds.y = viewport.y - offset.1;
+        }
+    }
+
+    fn process_event(&mut self, args: ProcessEventArgs<'_, C>) -> ProcessEventResult {
+        let pointer_event = match args.event.pointer_event() {
+            Some(pointer_event) => pointer_event,
+            None => return ProcessEventResult::default(),
+        };
+        let viewport = self.viewport(args.bounds.size);
+        let content_size = content_size(args.slots, args.slot_id);
+        let scrollbars = self.scrollbars(args.bounds, content_size);
+        let scrollbar = |axis| match axis {
+            Axis::Horizontal => scrollbars.horizontal,
+            Axis::Vertical => scrollbars.vertical,
+        };
+
+        let mut result = ProcessEventResult::default();
+        match pointer_event {
+            PointerEvent::Scrolled { x, y, delta_x, delta_y } => {
+                if args.bounds.contains_point(x, y) {
+                    let step = self.settings.wheel_step;
+                    let offset = (self.offset.0 - delta_x * step, self.offset.1 - delta_y * step);
+                    // Leave the wheel to enclosing containers once this one can't scroll further.
+                    result.handled = self.set_offset(args.slots, args.slot_id, offset);
+                }
+            }
+            PointerEvent::Pressed { x, y } => {
+                for &axis in &[Axis::Horizontal, Axis::Vertical] {
+                    let scrollbar = match scrollbar(axis) {
+                        Some(scrollbar) if scrollbar.track.contains_point(x, y) => scrollbar,
+                        _ => continue,
+                    };
+                    let (position, thumb_start, thumb_length) = match axis {
+                        Axis::Horizontal => (x, scrollbar.thumb.x, scrollbar.thumb.size.width),
+                        Axis::Vertical => (y, scrollbar.thumb.y, scrollbar.thumb.size.height),
+                    };
+                    // Pressing the track outside the thumb centres the thumb on the pointer.
+                    let grab_offset = if scrollbar.thumb.contains_point(x, y) {
+                        position - thumb_start
+                    } else {
+                        let grab_offset = thumb_length / scalar::TWO;
+                        let offset = self.offset_for_thumb(
+                            &scrollbar,
+                            axis,
+                            position - grab_offset,
+                            viewport,
+                            content_size,
+                        );
+                        self.set_offset(args.slots, args.slot_id, offset);
+                        grab_offset
+                    };
+                    self.drag = Some(Drag { axis, grab_offset });
+                }
+            }
+            PointerEvent::Moved { x, y } => {
+                if let Some(drag) = self.drag {
+                    if let Some(scrollbar) = scrollbar(drag.axis) {
+                        let position = match drag.axis {
+                            Axis::Horizontal => x,
+                            Axis::Vertical => y,
+                        };
+                        let offset = self.offset_for_thumb(
+                            &scrollbar,
+                            drag.axis,
+                            position - drag.grab_offset,
+                            viewport,
+                            content_size,
+                        );
+                        self.set_offset(args.slots, args.slot_id, offset);
+                    }
+                }
+            }
+            PointerEvent::Released { .. } => self.drag = None,
+        }
+        result
+    }
+
+    fn draw(&self, args: DrawArgs<'_, C>) {
+        if let Some(painter) = self.painter {
+            let scrollbars = self.scrollbars(args.bounds, self.content_size.get());
+            painter(args, &scrollbars);
+        }
+    }
+
+    fn clip_rect(&self, size: Dimensions) -> Option<Bounds> {
+        Some(self.viewport(size))
+    }
+}
+
+impl<C: Context> Gui<C> {
+    /// Scrolls the `ScrollContainer` bound to `slot_id` so that `(x, y)` in its content is at the
+    /// top left corner of its viewport, as far as the content allows. Takes effect immediately,
+    /// using the sizes from the last layout.
+    pub fn scroll_to(&mut self, slot_id: SlotId, x: Scalar, y: Scalar) -> Result<(), GuiError> {
+        let widget_id = self.slots.try_get(slot_id)?.widget_id.ok_or(GuiError::NoWidget(slot_id))?;
+        let widget = self.widgets.get_mut(widget_id.0).unwrap();
+        let found_kind_id = widget.kind_id();
+        let scroll_container = widget
+            .downcast_mut::<ScrollContainer<C>>()
+            .ok_or_else(|| GuiError::WrongWidgetType {
+                expected: std::any::type_name::<ScrollContainer<C>>(),
+                found_kind_id,
+            })?;
+        scroll_container.set_offset(&mut self.slots, slot_id, (x, y));
+        Ok(())
+    }
+
+    /// Scrolls every `ScrollContainer` that `slot_id` is inside of, innermost first, just far
+    /// enough to bring the slot into view. Uses the sizes from the last layout.
+    pub fn ensure_visible(&mut self, slot_id: SlotId) -> Result<(), GuiError> {
+        // The slot's bounds, relative to the ancestor currently being looked at.
+        let mut bounds = self.slots.try_get(slot_id)?.bounds;
+        let mut ancestor_id = self.slots.get(slot_id).parent;
+        while let Some(container_id) = ancestor_id {
+            let container = self.slots.get(container_id);
+            ancestor_id = container.parent;
+            let container_bounds = container.bounds;
+            let widget = match container.widget_id {
+                Some(widget_id) => self.widgets.get_mut(widget_id.0).ok(),
+                None => None,
+            };
+            let scroll_container =
+                widget.and_then(|widget| widget.downcast_mut::<ScrollContainer<C>>());
+            if let Some(scroll_container) = scroll_container {
+                let viewport = scroll_container.viewport(container_bounds.size);
+                // Prefer showing the start of slots too large to fit.
+                let scroll_by = |start: Scalar, length: Scalar, viewport_start, viewport_length| {
+                    if start < viewport_start || length > viewport_length {
+                        start - viewport_start
+                    } else if start + length > viewport_start + viewport_length {
+                        start + length - viewport_start - viewport_length
+                    } else {
+                        scalar::ZERO
+                    }
+                };
+                let old_offset = scroll_container.offset;
+                let offset = (
+                    old_offset.0
+                        + scroll_by(bounds.x, bounds.size.width, viewport.x, viewport.size.width),
+                    old_offset.1
+                        + scroll_by(bounds.y, bounds.size.height, viewport.y, viewport.size.height),
+                );
+                scroll_container.set_offset(&mut self.slots, container_id, offset);
+                let new_offset = scroll_container.offset;
+                bounds.x -= new_offset.0 - old_offset.0;
+                bounds.y -= new_offset.1 - old_offset.1;
+            }
+            bounds.x += container_bounds.x;
+            bounds.y += container_bounds.y;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,300 @@
+//! A container that splits its space between its first two children, with a divider between them
+//! that can be dragged to resize them.
+//!
+//! The split offset is the size of the first child along the axis. Dragging never shrinks either
+//! child below its minimum size, but if `Settings::collapsible` is set, dragging the divider more
+//! than halfway past a child's minimum size collapses that child, hiding it until the divider is
+//! dragged back out. The container manages its children's `SlotInfo::hidden` flags to do this.
+
+use std::any::Any;
+use std::cell::Cell;
+use std::collections::HashMap;
+
+use crate::default_layout::Axis;
+use crate::{
+    scalar, Bounds, Context, Dimensions, DrawArgs, InputEvent, LayoutChildrenArgs,
+    MinimumSizeArgs, PointerEvent, ProcessEventArgs, ProcessEventResult, Scalar, Signal, Widget,
+};
+
+/// The name of the signal emitted when dragging the divider moves it or collapses a child. Its
+/// `offset` field holds the new split offset (a `Scalar`) and its `collapsed` field the collapsed
+/// side, if any (an `Option<Side>`).
+pub const SPLIT_CHANGED_SIGNAL: &str = "split_changed";
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// `Horizontal` places the children side by side, `Vertical` one above the other.
+    pub axis: Axis,
+    pub divider_thickness: Scalar,
+    /// Whether dragging the divider far enough collapses a child.
+    pub collapsible: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    First,
+    Second,
+}
+
+/// The divider as passed to a `Painter`.
+#[derive(Debug, Clone, Copy)]
+pub struct Divider {
+    /// Positioned relative to `DrawArgs::bounds`.
+    pub bounds: Bounds,
+    pub dragging: bool,
+}
+
+/// Draws a split container's divider.
+pub type Painter<C> = fn(DrawArgs<'_, C>, &Divider);
+
+/// What the last layout found, for hit-testing and dragging the divider.
+#[derive(Debug, Clone, Copy)]
+struct Arrangement {
+    /// The divider, relative to the container.
+    divider: Bounds,
+    /// The space along the axis shared by the children, excluding the divider.
+    available_length: Scalar,
+    first_minimum_length: Scalar,
+    second_minimum_length: Scalar,
+}
+
+pub struct SplitContainer<C: Context> {
+    settings: Settings,
+    painter: Option<Painter<C>>,
+    split_offset: Option<Scalar>,
+    collapsed: Option<Side>,
+    /// Where along the divider it was grabbed, while it's being dragged.
+    drag: Option<Scalar>,
+    arrangement: Cell<Option<Arrangement>>,
+}
+
+impl<C: Context> SplitContainer<C> {
+    /// A split container that splits its space evenly until the divider is moved.
+    pub fn new(settings: Settings) -> SplitContainer<C> {
+        SplitContainer {
+            settings,
+            painter: None,
+            split_offset: None,
+            collapsed: None,
+            drag: None,
+            arrangement: Cell::new(None),
+        }
+    }
+
+    pub fn with_painter(mut self, painter: Painter<C>) -> SplitContainer<C> {
+        self.painter = Some(painter);
+        self
+    }
+
+    /// Starts at `split_offset`, e.g. one saved from a `SPLIT_CHANGED_SIGNAL`.
+    pub fn with_split_offset(mut self, split_offset: Scalar) -> SplitContainer<C> {
+        self.split_offset = Some(split_offset);
+        self
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// The size of the first child along the axis, as last set. The children's minimum sizes may
+    /// keep the layout from honouring it exactly. `None` until the divider is first moved.
+    pub fn split_offset(&self) -> Option<Scalar> {
+        self.split_offset
+    }
+
+    /// Takes effect on the next layout.
+    pub fn set_split_offset(&mut self, split_offset: Scalar) {
+        self.split_offset = Some(split_offset);
+    }
+
+    pub fn collapsed(&self) -> Option<Side> {
+        self.collapsed
+    }
+
+    /// Collapses a side (or restores both with `None`). Takes effect on the next layout.
+    pub fn set_collapsed(&mut self, collapsed: Option<Side>) {
+        self.collapsed = collapsed;
+    }
+
+    fn main_length(&self, size: Dimensions) -> Scalar {
+        match self.settings.axis {
+            Axis::Horizontal => size.width,
+            Axis::Vertical => size.height,
+        }
+    }
+
+    /// Moves the divider so that it starts at `position` along the axis, collapsing or restoring
+    /// children as needed. Returns whether anything changed.
+    fn drag_to(&mut self, position: Scalar, arrangement: &Arrangement) -> bool {
+        let Arrangement {
+            available_length,
+            first_minimum_length,
+            second_minimum_length,
+            ..
+        } = *arrangement;
+        let (split_offset, collapsed) = if self.settings.collapsible
+            && position < first_minimum_length / scalar::TWO
+        {
+            (self.split_offset, Some(Side::First))
+        } else if self.settings.collapsible
+            && position > available_length - second_minimum_length / scalar::TWO
+        {
+            (self.split_offset, Some(Side::Second))
+        } else {
+            let split_offset = position
+                .min(available_length - second_minimum_length)
+                .max(first_minimum_length);
+            (Some(split_offset), None)
+        };
+        let changed = split_offset != self.split_offset || collapsed != self.collapsed;
+        self.split_offset = split_offset;
+        self.collapsed = collapsed;
+        changed
+    }
+}
+
+impl<C: Context> Widget<C> for SplitContainer<C> {
+    fn kind_id(&self) -> &'static str {
+        "SplitContainer"
+    }
+
+    fn takes_focus(&self) -> bool {
+        false
+    }
+
+    fn minimum_size(&self, args: MinimumSizeArgs<'_, C>) -> Dimensions {
+        let children = args.slots.get(args.slot_id).children();
+        let minimum_size = |index: usize, side: Side| {
+            children
+                .get(index)
+                .filter(|_| self.collapsed != Some(side))
+                .map_or(Dimensions::zero(), |child_id| args.minimum_size_cache[child_id])
+        };
+        let first = minimum_size(0, Side::First);
+        let second = minimum_size(1, Side::Second);
+        let divider_thickness = self.settings.divider_thickness;
+        match self.settings.axis {
+            Axis::Horizontal => Dimensions::new(
+                first.width + divider_thickness + second.width,
+                first.height.max(second.height),
+            ),
+            Axis::Vertical => Dimensions::new(
+                first.width.max(second.width),
+                first.height + divider_thickness + second.height,
+            ),
+        }
+    }
+
+    fn layout_children(&self, args: LayoutChildrenArgs<'_, C>) {
+        let Settings { axis, divider_thickness, .. } = self.settings;
+        let size = args.slots.get(args.slot_id).bounds.size;
+        let children = args.slots.get(args.slot_id).children().to_vec();
+        let (first_id, second_id) = match children[..] {
+            [first_id, second_id, ..] => (first_id, second_id),
+            _ => return,
+        };
+
+        let available_length = self.main_length(size) - divider_thickness;
+        let first_minimum_length = self.main_length(args.minimum_size_cache[&first_id]);
+        let second_minimum_length = self.main_length(args.minimum_size_cache[&second_id]);
+        let first_length = match self.collapsed {
+            Some(Side::First) => scalar::ZERO,
+            Some(Side::Second) => available_length,
+            None => self
+                .split_offset
+                .unwrap_or(available_length / scalar::TWO)
+                .min(available_length - second_minimum_length)
+                .max(first_minimum_length),
+        };
+        let second_length = (available_length - first_length).max(scalar::ZERO);
+
+        let (first, divider, second) = match axis {
+            Axis::Horizontal => (
+                Bounds::new(scalar::ZERO, scalar::ZERO, first_length, size.height),
+                Bounds::new(first_length, scalar::ZERO, divider_thickness, size.height),
+                Bounds::new(
+                    first_length + divider_thickness,
+                    scalar::ZERO,
+                    second_length,
+                    size.height,
+                ),
+            ),
+            Axis::Vertical => (
+                Bounds::new(scalar::ZERO, scalar::ZERO, size.width, first_length),
+                Bounds::new(scalar::ZERO, first_length, size.width, divider_thickness),
+                Bounds::new(
+                    scalar::ZERO,
+                    first_length + divider_thickness,
+                    size.width,
+                    second_length,
+                ),
+            ),
+        };
+        let sides = [(first_id, first, Side::First), (second_id, second, Side::Second)];
+        for &(child_id, bounds, side) in &sides {
+            let child = args.slots.get_mut(child_id);
+            child.bounds = bounds;
+            child.info.hidden = self.collapsed == Some(side);
+        }
+        self.arrangement.set(Some(Arrangement {
+            divider,
+            available_length,
+            first_minimum_length,
+            second_minimum_length,
+        }));
+    }
+
+    fn process_event(&mut self, args: ProcessEventArgs<'_, C>) -> ProcessEventResult {
+        let (pointer_event, arrangement) =
+            match (args.event.pointer_event(), self.arrangement.get()) {
+                (Some(pointer_event), Some(arrangement)) => (pointer_event, arrangement),
+                _ => return ProcessEventResult::default(),
+            };
+        let position = |x, y| match self.settings.axis {
+            Axis::Horizontal => x,
+            Axis::Vertical => y,
+        };
+        let divider_start = position(arrangement.divider.x, arrangement.divider.y);
+
+        let mut result = ProcessEventResult::default();
+        match pointer_event {
+            PointerEvent::Pressed { x, y } => {
+                if arrangement.divider.contains_point(x, y) {
+                    self.drag = Some(position(x, y) - divider_start);
+                }
+            }
+            PointerEvent::Moved { x, y } => {
+                if let Some(grab_offset) = self.drag {
+                    if self.drag_to(position(x, y) - grab_offset, &arrangement) {
+                        // Relayout to apply the new split.
+                        args.slots.dirty = true;
+                        let mut fields: HashMap<String, Box<dyn Any>> = HashMap::new();
+                        fields.insert(
+                            "offset".to_string(),
+                            Box::new(self.split_offset.unwrap_or(divider_start)),
+                        );
+                        fields.insert("collapsed".to_string(), Box::new(self.collapsed));
+                        result.signals.push(Signal::with_fields(SPLIT_CHANGED_SIGNAL, fields));
+                    }
+                }
+            }
+            PointerEvent::Released { .. } => self.drag = None,
+            PointerEvent::Scrolled { .. } => {}
+        }
+        result
+    }
+
+    fn draw(&self, args: DrawArgs<'_, C>) {
+        if let (Some(painter), Some(arrangement)) = (self.painter, self.arrangement.get()) {
+            let divider = Divider {
+                bounds: Bounds {
+                    x: arrangement.divider.x + args.bounds.x,
+                    y: arrangement.divider.y + args.bounds.y,
+                    ..arrangement.divider
+                },
+                dragging: self.drag.is_some(),
+            };
+            painter(args, &divider);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//! A container that shows one of its children at a time, with a bar of tabs above them for
+//! switching between them.
+//!
+//! The container manages its children's `SlotInfo::hidden` flags, hiding all but the current
+//! tab's child. Its minimum size accounts for every child, so switching tabs doesn't change it.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::{
+    scalar, Bounds, Context, Dimensions, DrawArgs, InputEvent, Key, KeyEvent, LayoutChildrenArgs,
+    MinimumSizeArgs, PointerEvent, ProcessEventArgs, ProcessEventResult, Scalar, Signal, SlotId,
+    Widget,
+};
+
+/// The name of the signal emitted when the user switches tabs. Its `index` field holds the new
+/// tab's index (a `usize`) and its `slot_id` field the new tab's child (a `SlotId`).
+pub const TAB_CHANGED_SIGNAL: &str = "tab_changed";
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub tab_bar_height: Scalar,
+    /// The width of each tab in the bar. Tabs are placed left to right.
+    pub tab_width: Scalar,
+}
+
+/// A tab as passed to a `Painter`.
+#[derive(Debug, Clone)]
+pub struct Tab {
+    pub title: String,
+    /// Positioned relative to `DrawArgs::bounds`.
+    pub bounds: Bounds,
+    pub current: bool,
+}
+
+/// Draws a tab container's tab bar.
+pub type Painter<C> = fn(DrawArgs<'_, C>, &[Tab]);
+
+/// The title and bounds (relative to the container) of a tab, as of the last layout.
+#[derive(Debug, Clone)]
+struct TabEntry {
+    slot_id: SlotId,
+    title: String,
+    bounds: Bounds,
+}
+
+pub struct TabContainer<C: Context> {
+    settings: Settings,
+    painter: Option<Painter<C>>,
+    current_tab: usize,
+    titles: HashMap<SlotId, String>,
+    tabs: RefCell<Vec<TabEntry>>,
+}
+
+impl<C: Context> TabContainer<C> {
+    pub fn new(settings: Settings) -> TabContainer<C> {
+        TabContainer {
+            settings,
+            painter: None,
+            current_tab: 0,
+            titles: HashMap::new(),
+            tabs: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn with_painter(mut self, painter: Painter<C>) -> TabContainer<C> {
+        self.painter = Some(painter);
+        self
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// The index of the child currently shown, as last set.
+    pub fn current_tab(&self) -> usize {
+        self.current_tab
+    }
+
+    /// The current tab, limited to the tabs found by the last layout.
+    fn shown_tab(&self) -> usize {
+        self.current_tab.min(self.tabs.borrow().len().saturating_sub(1))
+    }
+
+    /// Shows the child at `index`. Takes effect on the next layout; an index past the last child
+    /// shows the last child.
+    pub fn set_current_tab(&mut self, index: usize) {
+        self.current_tab = index;
+    }
+
+    /// Titles the tab for `slot_id`. Tabs without a title use their slot's name, or failing that
+    /// their position, starting from "Tab 1".
+    pub fn set_tab_title<T: Into<String>>(&mut self, slot_id: SlotId, title: T) {
+        self.titles.insert(slot_id, title.into());
+    }
+
+    /// Switches to the tab at `index`, returning the signal to emit if it changed.
+    fn switch_to(&mut self, index: usize) -> Option<Signal> {
+        let slot_id = self.tabs.borrow().get(index)?.slot_id;
+        if index == self.shown_tab() {
+            return None;
+        }
+        self.current_tab = index;
+        let mut fields: HashMap<String, Box<dyn Any>> = HashMap::new();
+        fields.insert("index".to_string(), Box::new(index));
+        fields.insert("slot_id".to_string(), Box::new(slot_id));
+        Some(Signal::with_fields(TAB_CHANGED_SIGNAL, fields))
+    }
+}
+
+impl<C: Context> Widget<C> for TabContainer<C> {
+    fn kind_id(&self) -> &'static str {
+        "TabContainer"
+    }
+
+    fn takes_focus(&self) -> bool {
+        true
+    }
+
+    fn minimum_size(&self, args: MinimumSizeArgs<'_, C>) -> Dimensions {
+        let Settings { tab_bar_height, tab_width } = self.settings;
+        // Hidden children count too, so that switching tabs doesn't change the minimum size.
+        let children = args.slots.get(args.slot_id).children();
+        let content_size = children.iter().fold(Dimensions::zero(), |size, child_id| {
+            let child_minimum_size = args.minimum_size_cache[child_id];
+            Dimensions::new(
+                size.width.max(child_minimum_size.width),
+                size.height.max(child_minimum_size.height),
+            )
+        });
+        Dimensions::new(
+            content_size.width.max(tab_width * children.len() as Scalar),
+            content_size.height + tab_bar_height,
+        )
+    }
+
+    fn layout_children(&self, args: LayoutChildrenArgs<'_, C>) {
+        let Settings { tab_bar_height, tab_width } = self.settings;
+        let size = args.slots.get(args.slot_id).bounds.size;
+        let children = args.slots.get(args.slot_id).children().to_vec();
+        let current_tab = self.current_tab.min(children.len().saturating_sub(1));
+
+        let mut tabs = self.tabs.borrow_mut();
+        tabs.clear();
+        let mut x = scalar::ZERO;
+        for (index, &child_id) in children.iter().enumerate() {
+            let child = args.slots.get_mut(child_id);
+            child.info.hidden = index != current_tab;
+            child.bounds = Bounds::new(
+                scalar::ZERO,
+                tab_bar_height,
+                size.width,
+                (size.height - tab_bar_height).max(scalar::ZERO),
+            );
+            let title = self
+                .titles
+                .get(&child_id)
+                .cloned()
+                .or_else(|| child.name().map(str::to_string))
+                .unwrap_or_else(|| format!("Tab {}", index + 1));
+            tabs.push(TabEntry {
+                slot_id: child_id,
+                title,
+                bounds: Bounds::new(x, scalar::ZERO, tab_width, tab_bar_height),
+            });
+            x += tab_width;
+        }
+    }
+
+    fn process_event(&mut self, args: ProcessEventArgs<'_, C>) -> ProcessEventResult {
+        let mut result = ProcessEventResult::default();
+        let num_tabs = self.tabs.borrow().len();
+        if num_tabs == 0 {
+            return result;
+        }
+
+        let current_tab = self.shown_tab();
+        let mut new_tab = None;
+        if let Some(PointerEvent::Pressed { x, y }) = args.event.pointer_event() {
+            new_tab = self
+                .tabs
+                .borrow()
+                .iter()
+                .position(|tab| tab.bounds.contains_point(x, y));
+            result.request_focus = new_tab.is_some();
+        }
+        // Ctrl+Tab and Ctrl+Shift+Tab switch to the next and previous tabs.
+        if args.focused {
+            if let Some(KeyEvent::Pressed { key: Key::Tab, modifiers }) = args.event.key_event() {
+                if modifiers.control && !modifiers.alt {
+                    new_tab = Some(if modifiers.shift {
+                        (current_tab + num_tabs - 1) % num_tabs
+                    } else {
+                        (current_tab + 1) % num_tabs
+                    });
+                }
+            }
+        }
+        if let Some(signal) = new_tab.and_then(|index| self.switch_to(index)) {
+            // Relayout to show the new tab.
+            args.slots.dirty = true;
+            result.signals.push(signal);
+        }
+        result
+    }
+
+    fn draw(&self, args: DrawArgs<'_, C>) {
+        if let Some(painter) = self.painter {
+            let current_tab = self.shown_tab();
+            let tabs = self
+                .tabs
+                .borrow()
+                .iter()
+                .enumerate()
+                .map(|(index, tab)| Tab {
+                    title: tab.title.clone(),
+                    bounds: Bounds {
+                        x: tab.bounds.x + args.bounds.x,
+                        y: tab.bounds.y + args.bounds.y,
+                        ..tab.bounds
+                    },
+                    current: index == current_tab,
+                })
+                .collect::<Vec<_>>();
+            painter(args, &tabs);
+        }
+    }
+}
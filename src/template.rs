@@ -0,0 +1,335 @@
+//! Reusable subtree descriptions that can be instantiated any number of times.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::clone::clone_widget;
+use crate::registry::Constructor;
+use crate::{
+    is_valid_name, Context, Gui, GuiError, Properties, SlotId, SlotInfo, Widget, WidgetRegistry,
+};
+
+type Factory<C> = dyn Fn() -> Result<Box<dyn Widget<C>>, GuiError>;
+
+/// Describes how to construct a fresh widget for each instance of a `Template`.
+pub struct WidgetSpec<C: Context> {
+    create: Rc<Factory<C>>,
+}
+
+impl<C: Context> WidgetSpec<C> {
+    /// Each instance gets a clone of `prototype`.
+    pub fn from_clone<W: Widget<C> + Clone>(prototype: W) -> WidgetSpec<C> {
+        WidgetSpec::from_fn(move || prototype.clone())
+    }
+
+    /// Each instance gets a widget returned by `constructor`.
+    pub fn from_fn<W, F>(constructor: F) -> WidgetSpec<C>
+    where
+        W: Widget<C>,
+        F: Fn() -> W + 'static,
+    {
+        WidgetSpec {
+            create: Rc::new(move || Ok(Box::new(constructor()) as Box<dyn Widget<C>>)),
+        }
+    }
+
+    /// Each instance gets a widget constructed by `registry` from `properties`.
+    pub fn from_registry(
+        registry: &WidgetRegistry<C>,
+        kind_id: &str,
+        properties: Properties,
+    ) -> Result<WidgetSpec<C>, GuiError> {
+        let constructor: Rc<Constructor<C>> = registry.constructor(kind_id)?;
+        Ok(WidgetSpec {
+            create: Rc::new(move || constructor(&properties)),
+        })
+    }
+
+    /// Each instance gets a clone of `prototype`, which must support `Widget::as_clone_widget`.
+    pub fn from_boxed_clone(prototype: Box<dyn Widget<C>>) -> Result<WidgetSpec<C>, GuiError> {
+        clone_widget(&*prototype)?;
+        Ok(WidgetSpec {
+            create: Rc::new(move || clone_widget(&*prototype)),
+        })
+    }
+
+    pub fn create(&self) -> Result<Box<dyn Widget<C>>, GuiError> {
+        (self.create)()
+    }
+}
+
+impl<C: Context> Clone for WidgetSpec<C> {
+    fn clone(&self) -> Self {
+        WidgetSpec {
+            create: Rc::clone(&self.create),
+        }
+    }
+}
+
+/// A subtree of slots, widgets and style overrides that can be instantiated repeatedly with
+/// `Gui::instantiate`. Built with `Template::build` or captured from a live subtree with
+/// `Gui::capture_template`.
+pub struct Template<C: Context> {
+    /// Nodes in pre-order; the first node is the template's root.
+    nodes: Vec<TemplateNode<C>>,
+}
+
+struct TemplateNode<C: Context> {
+    parent: Option<usize>,
+    name: Option<String>,
+    info: SlotInfo,
+    widget: Option<WidgetSpec<C>>,
+    style: HashMap<String, C::StyleFieldValue>,
+}
+
+impl<C: Context> Template<C> {
+    /// Builds a template with a root slot described by `info` and `widget`, calling `children` to
+    /// add slots beneath it.
+    pub fn build<F>(
+        info: SlotInfo,
+        widget: Option<WidgetSpec<C>>,
+        children: F,
+    ) -> Result<Template<C>, GuiError>
+    where
+        F: FnOnce(&mut TemplateBuilder<'_, C>),
+    {
+        let mut template = Template {
+            nodes: vec![TemplateNode {
+                parent: None,
+                name: None,
+                info,
+                widget,
+                style: HashMap::new(),
+            }],
+        };
+        let mut builder = TemplateBuilder {
+            template: &mut template,
+            parent: 0,
+            current: None,
+            error: None,
+        };
+        children(&mut builder);
+        match builder.error {
+            Some(err) => Err(err),
+            None => Ok(template),
+        }
+    }
+
+    /// The path of each node relative to the template's root, for nodes reachable through named
+    /// slots. The root itself is `.`.
+    fn paths(&self) -> Vec<Option<String>> {
+        let mut paths: Vec<Option<String>> = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            paths.push(match (node.parent, node.name.as_ref()) {
+                (None, _) => Some(".".to_string()),
+                (Some(parent), Some(name)) => match paths[parent].as_deref() {
+                    Some(".") => Some(name.clone()),
+                    Some(parent_path) => Some(format!("{}/{}", parent_path, name)),
+                    None => None,
+                },
+                (Some(_), None) => None,
+            });
+        }
+        paths
+    }
+}
+
+/// Adds slots to a `Template`. Mirrors `TreeBuilder`, but takes `WidgetSpec`s instead of widgets.
+pub struct TemplateBuilder<'a, C: Context> {
+    template: &'a mut Template<C>,
+    parent: usize,
+    current: Option<usize>,
+    error: Option<GuiError>,
+}
+
+impl<'a, C: Context> TemplateBuilder<'a, C> {
+    /// Adds a slot with a widget, then calls `children` to add slots beneath it.
+    pub fn child<F>(&mut self, info: SlotInfo, widget: WidgetSpec<C>, children: F) -> &mut Self
+    where
+        F: FnOnce(&mut TemplateBuilder<'_, C>),
+    {
+        self.add_node(info, Some(widget), children)
+    }
+
+    /// Adds a slot with a widget that has no children.
+    pub fn leaf(&mut self, info: SlotInfo, widget: WidgetSpec<C>) -> &mut Self {
+        self.add_node(info, Some(widget), |_| {})
+    }
+
+    /// Adds a slot without a widget, then calls `children` to add slots beneath it.
+    pub fn slot<F>(&mut self, info: SlotInfo, children: F) -> &mut Self
+    where
+        F: FnOnce(&mut TemplateBuilder<'_, C>),
+    {
+        self.add_node(info, None, children)
+    }
+
+    /// Names the current slot. Names follow the same rules as `Slots::set_name`, and named paths
+    /// are the keys of the map returned by `Gui::instantiate`.
+    pub fn name<T: Into<String>>(&mut self, name: T) -> &mut Self {
+        if self.error.is_some() {
+            return self;
+        }
+        let current = self.current.expect("no slot to name");
+        let name = name.into();
+        if !is_valid_name(&name) {
+            self.error = Some(GuiError::InvalidName(name));
+            return self;
+        }
+        let parent = self.parent;
+        let duplicate = self.template.nodes
+            .iter()
+            .any(|node| node.parent == Some(parent) && node.name.as_ref() == Some(&name));
+        if duplicate {
+            self.error = Some(GuiError::DuplicateName { parent_id: None, name });
+            return self;
+        }
+        self.template.nodes[current].name = Some(name);
+        self
+    }
+
+    /// Overrides a style field of the current slot.
+    pub fn style<T: Into<String>, U: Into<C::StyleFieldValue>>(
+        &mut self,
+        field_name: T,
+        value: U,
+    ) -> &mut Self {
+        if self.error.is_some() {
+            return self;
+        }
+        let current = self.current.expect("no slot to style");
+        self.template.nodes[current]
+            .style
+            .insert(field_name.into(), value.into());
+        self
+    }
+
+    fn add_node<F>(&mut self, info: SlotInfo, widget: Option<WidgetSpec<C>>, children: F) -> &mut Self
+    where
+        F: FnOnce(&mut TemplateBuilder<'_, C>),
+    {
+        if self.error.is_some() {
+            return self;
+        }
+        let index = self.template.nodes.len();
+        self.template.nodes.push(TemplateNode {
+            parent: Some(self.parent),
+            name: None,
+            info,
+            widget,
+            style: HashMap::new(),
+        });
+        self.current = Some(index);
+        let mut builder = TemplateBuilder {
+            template: &mut *self.template,
+            parent: index,
+            current: None,
+            error: None,
+        };
+        children(&mut builder);
+        self.error = builder.error;
+        self
+    }
+}
+
+impl<C: Context> Gui<C> {
+    /// Captures `slot_id` and its descendants as a `Template`. Widgets that support
+    /// `Widget::as_clone_widget` are cloned for each instance; others are recreated through
+    /// `registry`, from the properties they write with `Widget::write_properties`.
+    pub fn capture_template(
+        &self,
+        registry: &WidgetRegistry<C>,
+        slot_id: SlotId,
+    ) -> Result<Template<C>, GuiError>
+    where
+        C::StyleFieldValue: Clone,
+    {
+        self.slots.try_get(slot_id)?;
+        let mut indices = HashMap::new();
+        let mut nodes = Vec::new();
+        for node_slot_id in std::iter::once(slot_id).chain(self.slots.descendants(slot_id)) {
+            let slot = self.slots.get(node_slot_id);
+            let parent = if node_slot_id == slot_id {
+                None
+            } else {
+                slot.parent.map(|parent_id| indices[&parent_id])
+            };
+            let widget = match slot.widget_id {
+                Some(widget_id) => {
+                    let widget = self.widgets.get(widget_id.0).unwrap();
+                    if widget.as_clone_widget().is_some() {
+                        Some(WidgetSpec::from_boxed_clone(clone_widget(&**widget)?)?)
+                    } else {
+                        let mut properties = Properties::new();
+                        widget.write_properties(&mut properties);
+                        Some(WidgetSpec::from_registry(registry, widget.kind_id(), properties)?)
+                    }
+                }
+                None => None,
+            };
+            indices.insert(node_slot_id, nodes.len());
+            nodes.push(TemplateNode {
+                parent,
+                name: slot.name().map(str::to_string),
+                info: slot.info.clone(),
+                widget,
+                style: self.slot_style_overrides
+                    .get(&node_slot_id)
+                    .cloned()
+                    .unwrap_or_default(),
+            });
+        }
+        Ok(Template { nodes })
+    }
+
+    /// Creates a new copy of `template` beneath `parent_id`. Returns the new slot ids keyed by
+    /// their path relative to the instance's root (which itself is keyed as `.`); slots that
+    /// can't be reached through named slots are omitted.
+    pub fn instantiate(
+        &mut self,
+        template: &Template<C>,
+        parent_id: SlotId,
+    ) -> Result<HashMap<String, SlotId>, GuiError>
+    where
+        C::StyleFieldValue: Clone,
+    {
+        self.slots.try_get(parent_id)?;
+        let mut widgets = Vec::with_capacity(template.nodes.len());
+        for node in &template.nodes {
+            widgets.push(match node.widget {
+                Some(ref spec) => Some(spec.create()?),
+                None => None,
+            });
+        }
+
+        let mut slot_ids: Vec<SlotId> = Vec::with_capacity(template.nodes.len());
+        for (node, widget) in template.nodes.iter().zip(widgets) {
+            let node_parent_id = node.parent.map_or(parent_id, |parent| slot_ids[parent]);
+            let slot_id = self.slots.add(node_parent_id, node.info.clone());
+            if let Some(ref name) = node.name {
+                if node.parent.is_some() {
+                    self.slots.set_name(slot_id, name.clone());
+                } else if let Err(err) = self.slots.try_set_name(slot_id, name.clone()) {
+                    // The root's name may clash with an existing child of `parent_id`.
+                    self.remove_slot(slot_id);
+                    return Err(err);
+                }
+            }
+            if let Some(widget) = widget {
+                let widget_id = self.add_boxed_widget(widget);
+                self.slots.get_mut(slot_id).widget_id = Some(widget_id);
+            }
+            if !node.style.is_empty() {
+                self.slot_style_overrides.insert(slot_id, node.style.clone());
+            }
+            slot_ids.push(slot_id);
+        }
+
+        Ok(template
+            .paths()
+            .into_iter()
+            .zip(slot_ids)
+            .filter_map(|(path, slot_id)| path.map(|path| (path, slot_id)))
+            .collect())
+    }
+}
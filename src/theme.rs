@@ -1,4 +1,7 @@
 use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::GuiError;
 
 pub struct SlotStyle<'a, R, S> {
     pub(super) widget_kind_id: &'static str,
@@ -8,15 +11,31 @@ pub struct SlotStyle<'a, R, S> {
 
 impl<'a, R, S> SlotStyle<'a, R, S> {
     pub fn get_field(&self, name: &str) -> &S {
-        self.get_field_opt(name).unwrap()
+        self.try_get_field(name).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like `get_field`, but reports a missing theme style or field as an error.
+    pub fn try_get_field(&self, name: &str) -> Result<&S, GuiError> {
+        if let Some(value) = self.field_overrides.and_then(|overrides| overrides.get(name)) {
+            return Ok(value);
+        }
+        self.theme
+            .try_get_widget_style(self.widget_kind_id)?
+            .get(name)
+            .ok_or_else(|| GuiError::MissingStyleField {
+                kind_id: self.widget_kind_id.to_string(),
+                field: name.to_string(),
+            })
     }
 
     pub fn get_field_opt(&self, name: &str) -> Option<&S> {
         self.field_overrides
             .and_then(|overrides| overrides.get(name))
             .or_else(|| {
-                let default_style = self.theme.get_widget_style(self.widget_kind_id);
-                default_style.get(name)
+                self.theme
+                    .try_get_widget_style(self.widget_kind_id)
+                    .ok()
+                    .and_then(|default_style| default_style.get(name))
             })
     }
 }
@@ -39,10 +58,79 @@ impl<R, S> Theme<R, S> {
             .insert(kind_id.to_string(), style);
     }
 
-    fn get_widget_style(&self, kind_id: &str) -> &HashMap<String, S> {
+    pub fn try_get_widget_style(&self, kind_id: &str) -> Result<&HashMap<String, S>, GuiError> {
         self.default_widget_styles
             .get(kind_id)
-            .ok_or_else(|| format!("theme missing style for widget: `{}`", kind_id))
-            .unwrap()
+            .ok_or_else(|| GuiError::MissingStyle {
+                kind_id: kind_id.to_string(),
+            })
+    }
+}
+
+impl<R, S: FromStr> Theme<R, S> {
+    /// Sets widget styles from text. Each section names a widget kind and replaces that kind's
+    /// style; kinds not mentioned are left alone.
+    ///
+    /// ```text
+    /// [Button]
+    /// color = #ff00ff
+    /// padding = 4
+    /// ```
+    ///
+    /// Values may be wrapped in double quotes to preserve surrounding whitespace. Nothing is
+    /// changed if the text fails to parse.
+    pub fn load_styles(&mut self, text: &str) -> Result<(), GuiError> {
+        let styles = parse_styles(text)?;
+        self.default_widget_styles.extend(styles);
+        Ok(())
+    }
+
+    /// Like `load_styles`, but first removes every existing style, so the theme ends up with
+    /// exactly the styles in `text`. Useful for hot-reloading a theme file.
+    pub fn reload_styles(&mut self, text: &str) -> Result<(), GuiError> {
+        self.default_widget_styles = parse_styles(text)?;
+        Ok(())
+    }
+}
+
+fn parse_styles<S: FromStr>(text: &str) -> Result<HashMap<String, HashMap<String, S>>, GuiError> {
+    let mut styles = HashMap::new();
+    let mut current_kind_id: Option<String> = None;
+    for (line_index, line) in text.lines().enumerate() {
+        let parse_error = |message: String| GuiError::ThemeParse {
+            line: line_index + 1,
+            message,
+        };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            if !line.ends_with(']') {
+                return Err(parse_error("expected `]` at end of section header".to_string()));
+            }
+            let kind_id = line[1..line.len() - 1].trim().to_string();
+            if styles.insert(kind_id.clone(), HashMap::new()).is_some() {
+                return Err(parse_error(format!("duplicate style for widget `{}`", kind_id)));
+            }
+            current_kind_id = Some(kind_id);
+        } else {
+            let style = current_kind_id
+                .as_ref()
+                .and_then(|kind_id| styles.get_mut(kind_id))
+                .ok_or_else(|| parse_error("expected a `[WidgetKind]` section".to_string()))?;
+            let equals_index = line
+                .find('=')
+                .ok_or_else(|| parse_error("expected `field = value`".to_string()))?;
+            let field = line[..equals_index].trim().to_string();
+            let value = crate::scene::parse_value(line[equals_index + 1..].trim())
+                .map_err(parse_error)?;
+            let parsed_value = value.parse().map_err(|_| GuiError::InvalidStyleValue {
+                field: field.clone(),
+                value,
+            })?;
+            style.insert(field, parsed_value);
+        }
     }
+    Ok(styles)
 }
@@ -0,0 +1,307 @@
+//! Iterators for walking the slot tree.
+//!
+//! All traversals that start from a slot exclude that slot itself. The `visible_*` variants skip
+//! hidden slots along with their entire subtrees, matching how layout, drawing and event
+//! processing treat `SlotInfo::hidden`.
+
+use crate::{SlotId, Slots};
+
+impl Slots {
+    pub fn parent(&self, slot_id: SlotId) -> Option<SlotId> {
+        self.get(slot_id).parent
+    }
+
+    /// Iterates over the ancestors of `slot_id`, starting with its parent and ending with the
+    /// root slot.
+    pub fn ancestors(&self, slot_id: SlotId) -> Ancestors<'_> {
+        Ancestors {
+            slots: self,
+            next: self.parent(slot_id),
+        }
+    }
+
+    /// Iterates over the descendants of `slot_id` in pre-order (parents before their children).
+    pub fn descendants(&self, slot_id: SlotId) -> Descendants<'_> {
+        Descendants::new(self, slot_id, false)
+    }
+
+    /// Like `descendants`, but skips hidden slots and their subtrees.
+    pub fn visible_descendants(&self, slot_id: SlotId) -> Descendants<'_> {
+        Descendants::new(self, slot_id, true)
+    }
+
+    /// Iterates over the descendants of `slot_id` in post-order (children before their parents).
+    pub fn descendants_post_order(&self, slot_id: SlotId) -> PostOrderDescendants<'_> {
+        PostOrderDescendants::new(self, slot_id, false)
+    }
+
+    /// Like `descendants_post_order`, but skips hidden slots and their subtrees.
+    pub fn visible_descendants_post_order(&self, slot_id: SlotId) -> PostOrderDescendants<'_> {
+        PostOrderDescendants::new(self, slot_id, true)
+    }
+
+    /// Iterates over the descendants of `slot_id` level by level. Like the other traversals, this
+    /// doesn't allocate; instead it walks down from `slot_id` again for each level.
+    pub fn breadth_first(&self, slot_id: SlotId) -> BreadthFirst<'_> {
+        BreadthFirst::new(self, slot_id, false)
+    }
+
+    /// Like `breadth_first`, but skips hidden slots and their subtrees.
+    pub fn visible_breadth_first(&self, slot_id: SlotId) -> BreadthFirst<'_> {
+        BreadthFirst::new(self, slot_id, true)
+    }
+
+    /// Iterates over the other children of `slot_id`'s parent, in order.
+    pub fn siblings(&self, slot_id: SlotId) -> Siblings<'_> {
+        Siblings::new(self, slot_id, false)
+    }
+
+    /// Like `siblings`, but skips hidden slots.
+    pub fn visible_siblings(&self, slot_id: SlotId) -> Siblings<'_> {
+        Siblings::new(self, slot_id, true)
+    }
+
+    /// The number of ancestors `slot_id` has. The root slot has a depth of zero.
+    pub fn depth(&self, slot_id: SlotId) -> usize {
+        self.ancestors(slot_id).count()
+    }
+
+    /// Returns `true` if `ancestor_id` is a (strict) ancestor of `slot_id`.
+    pub fn is_ancestor_of(&self, ancestor_id: SlotId, slot_id: SlotId) -> bool {
+        self.ancestors(slot_id).any(|id| id == ancestor_id)
+    }
+
+    /// Returns `true` if neither `slot_id` nor any of its ancestors are hidden.
+    pub fn is_visible_in_tree(&self, slot_id: SlotId) -> bool {
+        !self.get(slot_id).info.hidden
+            && self.ancestors(slot_id).all(|id| !self.get(id).info.hidden)
+    }
+
+    fn is_included(&self, slot_id: SlotId, visible_only: bool) -> bool {
+        !visible_only || !self.get(slot_id).info.hidden
+    }
+
+    /// The first (included) child of `slot_id`.
+    fn first_child(&self, slot_id: SlotId, visible_only: bool) -> Option<SlotId> {
+        self.get(slot_id)
+            .children
+            .iter()
+            .cloned()
+            .find(|&child_id| self.is_included(child_id, visible_only))
+    }
+
+    /// The next (included) sibling after `slot_id`.
+    fn next_sibling(&self, slot_id: SlotId, visible_only: bool) -> Option<SlotId> {
+        let parent_id = self.get(slot_id).parent?;
+        let children = &self.get(parent_id).children;
+        let index = children.iter().position(|&child_id| child_id == slot_id).unwrap();
+        children[index + 1..]
+            .iter()
+            .cloned()
+            .find(|&child_id| self.is_included(child_id, visible_only))
+    }
+}
+
+pub struct Ancestors<'a> {
+    slots: &'a Slots,
+    next: Option<SlotId>,
+}
+
+impl<'a> Iterator for Ancestors<'a> {
+    type Item = SlotId;
+
+    fn next(&mut self) -> Option<SlotId> {
+        let slot_id = self.next?;
+        self.next = self.slots.parent(slot_id);
+        Some(slot_id)
+    }
+}
+
+pub struct Descendants<'a> {
+    slots: &'a Slots,
+    root_id: SlotId,
+    next: Option<SlotId>,
+    visible_only: bool,
+}
+
+impl<'a> Descendants<'a> {
+    fn new(slots: &'a Slots, root_id: SlotId, visible_only: bool) -> Descendants<'a> {
+        Descendants {
+            slots,
+            root_id,
+            next: slots.first_child(root_id, visible_only),
+            visible_only,
+        }
+    }
+}
+
+impl<'a> Iterator for Descendants<'a> {
+    type Item = SlotId;
+
+    fn next(&mut self) -> Option<SlotId> {
+        let slot_id = self.next?;
+        self.next = self.slots.first_child(slot_id, self.visible_only).or_else(|| {
+            // Climb until we find an ancestor (or ourselves) with a following sibling.
+            let mut current_id = slot_id;
+            while current_id != self.root_id {
+                if let Some(sibling_id) = self.slots.next_sibling(current_id, self.visible_only) {
+                    return Some(sibling_id);
+                }
+                current_id = self.slots.parent(current_id).unwrap();
+            }
+            None
+        });
+        Some(slot_id)
+    }
+}
+
+pub struct PostOrderDescendants<'a> {
+    slots: &'a Slots,
+    root_id: SlotId,
+    next: Option<SlotId>,
+    visible_only: bool,
+}
+
+impl<'a> PostOrderDescendants<'a> {
+    fn new(slots: &'a Slots, root_id: SlotId, visible_only: bool) -> PostOrderDescendants<'a> {
+        let mut traversal = PostOrderDescendants {
+            slots,
+            root_id,
+            next: None,
+            visible_only,
+        };
+        traversal.next = slots
+            .first_child(root_id, visible_only)
+            .map(|child_id| traversal.deepest_first_descendant(child_id));
+        traversal
+    }
+
+    /// Follows first children down from `slot_id` until reaching a slot without any.
+    fn deepest_first_descendant(&self, mut slot_id: SlotId) -> SlotId {
+        while let Some(child_id) = self.slots.first_child(slot_id, self.visible_only) {
+            slot_id = child_id;
+        }
+        slot_id
+    }
+}
+
+impl<'a> Iterator for PostOrderDescendants<'a> {
+    type Item = SlotId;
+
+    fn next(&mut self) -> Option<SlotId> {
+        let slot_id = self.next?;
+        self.next = match self.slots.next_sibling(slot_id, self.visible_only) {
+            Some(sibling_id) => Some(self.deepest_first_descendant(sibling_id)),
+            None => self.slots
+                .parent(slot_id)
+                .filter(|&parent_id| parent_id != self.root_id),
+        };
+        Some(slot_id)
+    }
+}
+
+pub struct BreadthFirst<'a> {
+    slots: &'a Slots,
+    root_id: SlotId,
+    next: Option<SlotId>,
+    /// The depth of `next` below the root.
+    depth: usize,
+    visible_only: bool,
+}
+
+impl<'a> BreadthFirst<'a> {
+    fn new(slots: &'a Slots, root_id: SlotId, visible_only: bool) -> BreadthFirst<'a> {
+        BreadthFirst {
+            slots,
+            root_id,
+            next: slots.first_child(root_id, visible_only),
+            depth: 1,
+            visible_only,
+        }
+    }
+
+    /// The first slot after `slot_id` (which is `depth` levels below the root) in pre-order that
+    /// is exactly `target_depth` levels below the root, without visiting anything deeper.
+    fn next_at_depth(
+        &self,
+        mut slot_id: SlotId,
+        mut depth: usize,
+        target_depth: usize,
+    ) -> Option<SlotId> {
+        loop {
+            if depth < target_depth {
+                if let Some(child_id) = self.slots.first_child(slot_id, self.visible_only) {
+                    slot_id = child_id;
+                    depth += 1;
+                    if depth == target_depth {
+                        return Some(slot_id);
+                    }
+                    continue;
+                }
+            }
+            // Climb until we find an ancestor (or ourselves) with a following sibling.
+            loop {
+                if slot_id == self.root_id {
+                    return None;
+                }
+                if let Some(sibling_id) = self.slots.next_sibling(slot_id, self.visible_only) {
+                    slot_id = sibling_id;
+                    break;
+                }
+                slot_id = self.slots.parent(slot_id).unwrap();
+                depth -= 1;
+            }
+            if depth == target_depth {
+                return Some(slot_id);
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for BreadthFirst<'a> {
+    type Item = SlotId;
+
+    fn next(&mut self) -> Option<SlotId> {
+        let slot_id = self.next?;
+        self.next = self.next_at_depth(slot_id, self.depth, self.depth).or_else(|| {
+            // This level is done, so start the next one.
+            self.depth += 1;
+            self.next_at_depth(self.root_id, 0, self.depth)
+        });
+        Some(slot_id)
+    }
+}
+
+pub struct Siblings<'a> {
+    slots: &'a Slots,
+    slot_id: SlotId,
+    siblings: std::slice::Iter<'a, SlotId>,
+    visible_only: bool,
+}
+
+impl<'a> Siblings<'a> {
+    fn new(slots: &'a Slots, slot_id: SlotId, visible_only: bool) -> Siblings<'a> {
+        let siblings: &'a [SlotId] = match slots.parent(slot_id) {
+            Some(parent_id) => &slots.get(parent_id).children,
+            None => &[],
+        };
+        Siblings {
+            slots,
+            slot_id,
+            siblings: siblings.iter(),
+            visible_only,
+        }
+    }
+}
+
+impl<'a> Iterator for Siblings<'a> {
+    type Item = SlotId;
+
+    fn next(&mut self) -> Option<SlotId> {
+        let Siblings { slots, slot_id, visible_only, .. } = *self;
+        self.siblings
+            .by_ref()
+            .cloned()
+            .find(|&sibling_id| sibling_id != slot_id && slots.is_included(sibling_id, visible_only))
+    }
+}